@@ -0,0 +1,110 @@
+//! Times seeded generation for every difficulty, then compares the
+//! uniqueness check against the old approach of rescanning the row, column
+//! and box for every candidate digit.
+//!
+//! Run with `cargo bench --bench generation`.
+
+use std::time::{Duration, Instant};
+
+use sudoku_app::sudoku::{
+    Generator, Solver,
+    generator::{Difficulty, GeneratorOptions},
+};
+
+const ROUNDS: usize = 20;
+
+fn main() {
+    // Fixed seeds, so runs compare the same puzzles.
+    let options = GeneratorOptions::default();
+    for difficulty in Difficulty::ALL {
+        let seeded = time(|| {
+            for seed in 0..ROUNDS as u64 {
+                Generator::generate_seeded(difficulty, seed, &options);
+            }
+        });
+        println!("{difficulty:?}: generate_seeded {:?}/puzzle", seeded / ROUNDS as u32);
+    }
+
+    for difficulty in [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard] {
+        let mut puzzles = Vec::with_capacity(ROUNDS);
+        let generate = time(|| {
+            for _ in 0..ROUNDS {
+                puzzles.push(Generator::generate(difficulty).grid);
+            }
+        });
+
+        let masks = time(|| {
+            for puzzle in &puzzles {
+                assert_eq!(Solver::count_solutions(puzzle, 2), 1);
+            }
+        });
+        let scan = time(|| {
+            for puzzle in &puzzles {
+                assert_eq!(scan::count_solutions(puzzle, 2), 1);
+            }
+        });
+
+        println!(
+            "{difficulty:?}: generate {:?}/puzzle, uniqueness check {:?} (bitmasks) vs {:?} (rescan), {:.1}x",
+            generate / ROUNDS as u32,
+            masks / ROUNDS as u32,
+            scan / ROUNDS as u32,
+            scan.as_secs_f64() / masks.as_secs_f64(),
+        );
+    }
+}
+
+fn time(f: impl FnOnce()) -> Duration {
+    let start = Instant::now();
+    f();
+    start.elapsed()
+}
+
+/// The solution counter as it worked before `Grid` kept occupancy masks.
+mod scan {
+    use sudoku_app::sudoku::{Grid, Solver};
+
+    pub fn count_solutions(grid: &Grid, limit: usize) -> usize {
+        let mut grid = grid.clone();
+        let mut count = 0;
+        backtrack(&mut grid, 0, 0, &mut count, limit);
+        count
+    }
+
+    fn backtrack(grid: &mut Grid, row: usize, col: usize, count: &mut usize, limit: usize) {
+        let Some((row, col)) = Solver::find_next_empty(grid, row, col) else {
+            *count += 1;
+            return;
+        };
+
+        for num in 1..=9 {
+            if is_safe(grid, row, col, num) {
+                grid.set(row, col, num);
+                let (next_row, next_col) = if col == 8 { (row + 1, 0) } else { (row, col + 1) };
+                backtrack(grid, next_row, next_col, count, limit);
+                grid.set(row, col, 0);
+                if *count >= limit {
+                    return;
+                }
+            }
+        }
+    }
+
+    fn is_safe(grid: &Grid, row: usize, col: usize, num: u8) -> bool {
+        for i in 0..9 {
+            if grid.get(row, i) == num || grid.get(i, col) == num {
+                return false;
+            }
+        }
+        let box_row = (row / 3) * 3;
+        let box_col = (col / 3) * 3;
+        for r in box_row..box_row + 3 {
+            for c in box_col..box_col + 3 {
+                if grid.get(r, c) == num {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
@@ -0,0 +1,49 @@
+//! Compares the solver backends on generated puzzles and on 17-clue puzzles
+//! built to defeat row-major backtracking.
+//!
+//! Run with `cargo bench --bench solvers`.
+
+use std::time::{Duration, Instant};
+
+use sudoku_app::sudoku::{Generator, Grid, SolveStats, SolverKind, generator::Difficulty};
+
+const GENERATED: usize = 20;
+
+const HARDEST: &[&str] = &[
+    "000000010400000000020000000000050407008000300001090000300400200050100000000806000",
+    "000000000000003085001020000000507000004000100090000000500000073002010000000040009",
+];
+
+fn main() {
+    let generated: Vec<Grid> = (0..GENERATED).map(|_| Generator::generate(Difficulty::Hard).grid).collect();
+    let hardest: Vec<Grid> = HARDEST.iter().map(|puzzle| puzzle.parse().expect("valid puzzle")).collect();
+
+    for (set, puzzles) in [("generated hard", &generated), ("17-clue hardest", &hardest)] {
+        println!("{set} ({} puzzles):", puzzles.len());
+        for kind in SolverKind::ALL {
+            let mut total = SolveStats::default();
+            let mut elapsed = Duration::ZERO;
+
+            for puzzle in puzzles {
+                let mut grid = puzzle.clone();
+                let mut stats = SolveStats::default();
+                let start = Instant::now();
+                assert!(kind.backend().solve_with_stats(&mut grid, &mut stats));
+                elapsed += start.elapsed();
+
+                total.nodes += stats.nodes;
+                total.backtracks += stats.backtracks;
+                total.max_depth = total.max_depth.max(stats.max_depth);
+            }
+
+            println!(
+                "  {:<24} {:>12?}  nodes {:>10}  backtracks {:>10}  max depth {:>3}",
+                kind.name(),
+                elapsed,
+                total.nodes,
+                total.backtracks,
+                total.max_depth,
+            );
+        }
+    }
+}
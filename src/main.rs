@@ -1,12 +1,12 @@
 use eframe::egui;
-use sudoku_app::ui::app::SudokuApp;
+use sudoku_app::ui::app::{APP_NAME, SudokuApp};
 
 fn main() -> Result<(), eframe::Error> {
     println!("Starting Sudoku app...");
     
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
-            .with_inner_size([600.0, 700.0])
+            .with_inner_size([640.0, 900.0])
             .with_resizable(true)
             .with_title("Sudoku"),
         ..Default::default()
@@ -15,11 +15,11 @@ fn main() -> Result<(), eframe::Error> {
     println!("Creating window...");
     
     eframe::run_native(
-        "Sudoku",
+        APP_NAME,
         options,
-        Box::new(|_cc| {
+        Box::new(|cc| {
             println!("App created!");
-            Ok(Box::new(SudokuApp::default()))
+            Ok(Box::new(SudokuApp::new(cc)))
         }),
     )
 }
\ No newline at end of file
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+/// A set of digits 1-9 stored as a bitmask, bit `d - 1` standing for digit `d`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CandidateSet(u16);
+
+impl CandidateSet {
+    pub const EMPTY: Self = Self(0);
+    pub const ALL: Self = Self(0x1FF);
+
+    pub fn from_bits(bits: u16) -> Self {
+        Self(bits & Self::ALL.0)
+    }
+
+    pub fn single(digit: u8) -> Self {
+        Self(Self::bit(digit))
+    }
+
+    pub fn bits(self) -> u16 {
+        self.0
+    }
+
+    pub fn contains(self, digit: u8) -> bool {
+        self.0 & Self::bit(digit) != 0
+    }
+
+    pub fn insert(&mut self, digit: u8) {
+        self.0 |= Self::bit(digit);
+    }
+
+    pub fn remove(&mut self, digit: u8) {
+        self.0 &= !Self::bit(digit);
+    }
+
+    pub fn toggle(&mut self, digit: u8) {
+        self.0 ^= Self::bit(digit);
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// The digit in the set if it holds exactly one.
+    pub fn only(self) -> Option<u8> {
+        if self.len() == 1 {
+            Some(self.0.trailing_zeros() as u8 + 1)
+        } else {
+            None
+        }
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    pub fn difference(self, other: Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+
+    /// Digits in ascending order.
+    pub fn iter(self) -> impl Iterator<Item = u8> {
+        (1..=9).filter(move |&digit| self.contains(digit))
+    }
+
+    fn bit(digit: u8) -> u16 {
+        debug_assert!((1..=9).contains(&digit));
+        1 << (digit - 1)
+    }
+}
@@ -0,0 +1,280 @@
+use super::grid::Grid;
+use super::solver::{SolveStats, SolverBackend};
+
+/// Exact-cover solver using Knuth's Dancing Links (Algorithm X).
+///
+/// Every placement of a digit in a cell is a matrix row covering four
+/// constraints: the cell is filled, and the digit appears once in its row,
+/// column and box. Always branching on the constraint with the fewest
+/// remaining options keeps it fast on puzzles that defeat plain backtracking.
+pub struct Dlx;
+
+impl SolverBackend for Dlx {
+    fn name(&self) -> &'static str {
+        "Dancing Links"
+    }
+
+    fn solve_with_stats(&self, grid: &mut Grid, stats: &mut SolveStats) -> bool {
+        let Some(mut matrix) = Matrix::from_grid(grid) else {
+            return false;
+        };
+
+        let mut search = Search::new(1);
+        search.run(&mut matrix);
+        *stats = search.stats;
+
+        match search.first_solution {
+            Some(placements) => {
+                for placement in placements {
+                    let (row, col, digit) = Matrix::decode(placement);
+                    grid.set(row, col, digit);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn count_solutions(&self, grid: &Grid, limit: usize) -> usize {
+        if limit == 0 {
+            return 0;
+        }
+        let Some(mut matrix) = Matrix::from_grid(grid) else {
+            return 0;
+        };
+
+        let mut search = Search::new(limit);
+        search.run(&mut matrix);
+        search.count
+    }
+}
+
+const ROOT: usize = 0;
+// 81 cells, then digit-in-row, digit-in-column and digit-in-box.
+const COLUMNS: usize = 324;
+
+// Toroidal doubly linked lists stored as parallel arrays. Index 0 is the root,
+// 1..=COLUMNS are column headers, everything after that is a matrix node.
+struct Matrix {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    column: Vec<usize>,
+    placement: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl Matrix {
+    /// Builds the matrix with the grid's filled cells already selected, or
+    /// `None` if the filled cells conflict.
+    fn from_grid(grid: &Grid) -> Option<Self> {
+        if !grid.is_valid() {
+            return None;
+        }
+
+        let mut matrix = Self::with_headers();
+        let mut givens = Vec::new();
+
+        for row in 0..9 {
+            for col in 0..9 {
+                let value = grid.get(row, col);
+                for digit in 1..=9 {
+                    if value == 0 || value == digit {
+                        let first = matrix.add_row(row, col, digit);
+                        if value != 0 {
+                            givens.push(first);
+                        }
+                    }
+                }
+            }
+        }
+
+        for first in givens {
+            let mut node = first;
+            loop {
+                matrix.cover(matrix.column[node]);
+                node = matrix.right[node];
+                if node == first {
+                    break;
+                }
+            }
+        }
+        Some(matrix)
+    }
+
+    fn with_headers() -> Self {
+        let capacity = 1 + COLUMNS + 729 * 4;
+        let mut matrix = Self {
+            left: Vec::with_capacity(capacity),
+            right: Vec::with_capacity(capacity),
+            up: Vec::with_capacity(capacity),
+            down: Vec::with_capacity(capacity),
+            column: Vec::with_capacity(capacity),
+            placement: Vec::with_capacity(capacity),
+            size: vec![0; COLUMNS + 1],
+        };
+
+        for i in 0..=COLUMNS {
+            matrix.left.push(if i == 0 { COLUMNS } else { i - 1 });
+            matrix.right.push(if i == COLUMNS { 0 } else { i + 1 });
+            matrix.up.push(i);
+            matrix.down.push(i);
+            matrix.column.push(i);
+            matrix.placement.push(0);
+        }
+        matrix
+    }
+
+    /// Appends the row for placing `digit` at (`row`, `col`) and returns its
+    /// first node.
+    fn add_row(&mut self, row: usize, col: usize, digit: u8) -> usize {
+        let d = digit as usize - 1;
+        let box_idx = Grid::box_index(row, col);
+        let columns = [
+            1 + row * 9 + col,
+            1 + 81 + row * 9 + d,
+            1 + 162 + col * 9 + d,
+            1 + 243 + box_idx * 9 + d,
+        ];
+        let placement = (row * 9 + col) * 9 + d;
+
+        let first = self.left.len();
+        for (i, &header) in columns.iter().enumerate() {
+            let node = first + i;
+            self.left.push(if i == 0 { first + 3 } else { node - 1 });
+            self.right.push(if i == 3 { first } else { node + 1 });
+            self.up.push(self.up[header]);
+            self.down.push(header);
+            self.column.push(header);
+            self.placement.push(placement);
+
+            let above = self.up[header];
+            self.down[above] = node;
+            self.up[header] = node;
+            self.size[header] += 1;
+        }
+        first
+    }
+
+    fn decode(placement: usize) -> (usize, usize, u8) {
+        let cell = placement / 9;
+        (cell / 9, cell % 9, (placement % 9) as u8 + 1)
+    }
+
+    fn cover(&mut self, header: usize) {
+        let (l, r) = (self.left[header], self.right[header]);
+        self.right[l] = r;
+        self.left[r] = l;
+
+        let mut row = self.down[header];
+        while row != header {
+            let mut node = self.right[row];
+            while node != row {
+                let (u, d) = (self.up[node], self.down[node]);
+                self.down[u] = d;
+                self.up[d] = u;
+                self.size[self.column[node]] -= 1;
+                node = self.right[node];
+            }
+            row = self.down[row];
+        }
+    }
+
+    fn uncover(&mut self, header: usize) {
+        let mut row = self.up[header];
+        while row != header {
+            let mut node = self.left[row];
+            while node != row {
+                let (u, d) = (self.up[node], self.down[node]);
+                self.down[u] = node;
+                self.up[d] = node;
+                self.size[self.column[node]] += 1;
+                node = self.left[node];
+            }
+            row = self.up[row];
+        }
+
+        let (l, r) = (self.left[header], self.right[header]);
+        self.right[l] = header;
+        self.left[r] = header;
+    }
+
+    /// The uncovered column with the fewest rows, if any columns remain.
+    fn smallest_column(&self) -> Option<usize> {
+        let mut best = None;
+        let mut header = self.right[ROOT];
+        while header != ROOT {
+            if best.is_none_or(|b| self.size[header] < self.size[b]) {
+                best = Some(header);
+                if self.size[header] <= 1 {
+                    break;
+                }
+            }
+            header = self.right[header];
+        }
+        best
+    }
+}
+
+struct Search {
+    limit: usize,
+    count: usize,
+    chosen: Vec<usize>,
+    first_solution: Option<Vec<usize>>,
+    stats: SolveStats,
+}
+
+impl Search {
+    fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            count: 0,
+            chosen: Vec::with_capacity(81),
+            first_solution: None,
+            stats: SolveStats::default(),
+        }
+    }
+
+    /// Returns true once `limit` solutions have been found.
+    fn run(&mut self, matrix: &mut Matrix) -> bool {
+        let Some(header) = matrix.smallest_column() else {
+            self.count += 1;
+            if self.first_solution.is_none() {
+                self.first_solution = Some(self.chosen.clone());
+            }
+            return self.count >= self.limit;
+        };
+        if matrix.size[header] == 0 {
+            return false;
+        }
+
+        matrix.cover(header);
+        let mut row = matrix.down[header];
+        let mut done = false;
+        while row != header && !done {
+            self.chosen.push(matrix.placement[row]);
+            self.stats.enter(self.chosen.len());
+            let mut node = matrix.right[row];
+            while node != row {
+                matrix.cover(matrix.column[node]);
+                node = matrix.right[node];
+            }
+
+            done = self.run(matrix);
+
+            let mut node = matrix.left[row];
+            while node != row {
+                matrix.uncover(matrix.column[node]);
+                node = matrix.left[node];
+            }
+            self.chosen.pop();
+            if !done {
+                self.stats.backtracks += 1;
+            }
+            row = matrix.down[row];
+        }
+        matrix.uncover(header);
+        done
+    }
+}
@@ -1,24 +1,123 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
 use super::grid::Grid;
-use super::solver::Solver;
-use rand::Rng;
+use super::logic::Technique;
+use super::rating::{Rating, RatingBand};
+use super::solver::{self, Solver};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
 
 pub struct Generator;
 
+/// A generated puzzle together with its unique solution.
+#[derive(Clone, Debug, Default)]
+pub struct Puzzle {
+    /// The givens, marked fixed.
+    pub grid: Grid,
+    pub solution: Grid,
+}
+
+/// Limits on how long generation keeps retrying to hit a rating band. When
+/// either runs out, the closest puzzle found so far is returned. A time budget
+/// makes the result depend on machine speed, so seeded generation skips it and
+/// caps the attempts at [`Generator::SEEDED_MAX_ATTEMPTS`] instead.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GeneratorOptions {
+    pub max_attempts: usize,
+    pub time_budget: Option<Duration>,
+    /// Pattern the givens are laid out in.
+    pub symmetry: Symmetry,
+    /// Keep removing givens until none can go without losing uniqueness,
+    /// instead of stopping at the difficulty's removal target. With a
+    /// symmetry, givens are only removed together with their mirror images.
+    pub minimal: bool,
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> Self {
+        Self {
+            max_attempts: 500,
+            time_budget: Some(Duration::from_secs(2)),
+            symmetry: Symmetry::None,
+            minimal: false,
+        }
+    }
+}
+
 impl Generator {
-    pub fn generate(difficulty: Difficulty) -> Grid {
-        let mut grid = Grid::new();
-        let mut rng = rand::thread_rng();
+    /// Upper bound on attempts for seeded generation. Without a time budget,
+    /// this is what keeps a hard-to-hit band from stalling the caller, while
+    /// leaving enough tries that a game labelled Expert is one.
+    pub const SEEDED_MAX_ATTEMPTS: usize = 300;
 
-        Self::fill_diagonal_boxes(&mut grid);
-        Solver::solve(&mut grid);
+    pub fn generate(difficulty: Difficulty) -> Puzzle {
+        Self::generate_in_band(difficulty.band(), &GeneratorOptions::default())
+    }
 
-        let cells_to_remove = match difficulty {
-            Difficulty::Easy => 35,
-            Difficulty::Medium => 45,
-            Difficulty::Hard => 55,
+    /// Always returns the same puzzle for the same seed, difficulty and
+    /// options, so games can be shared by number. `options.time_budget` is
+    /// ignored and `options.max_attempts` is capped at
+    /// [`Generator::SEEDED_MAX_ATTEMPTS`].
+    pub fn generate_seeded(difficulty: Difficulty, seed: u64, options: &GeneratorOptions) -> Puzzle {
+        let options = GeneratorOptions {
+            max_attempts: options.max_attempts.min(Self::SEEDED_MAX_ATTEMPTS),
+            time_budget: None,
+            ..options.clone()
         };
+        Self::generate_with_rng(difficulty.band(), &options, &mut ChaCha8Rng::seed_from_u64(seed))
+    }
+
+    /// Generates puzzles until one's [`Rating`] falls inside `band`.
+    pub fn generate_in_band(band: RatingBand, options: &GeneratorOptions) -> Puzzle {
+        Self::generate_with_rng(band, options, &mut rand::thread_rng())
+    }
+
+    /// Like [`Generator::generate_in_band`], drawing all randomness from `rng`.
+    pub fn generate_with_rng(band: RatingBand, options: &GeneratorOptions, rng: &mut impl Rng) -> Puzzle {
+        let started = Instant::now();
+        let cells_to_remove = if options.minimal { 81 } else { Self::cells_to_remove(band) };
+        let mut closest: Option<(f32, Puzzle)> = None;
+
+        for _ in 0..options.max_attempts.max(1) {
+            let puzzle = Self::generate_puzzle(cells_to_remove, options.symmetry, rng);
+            let distance = band.distance(&Rating::of(&puzzle.grid));
+            if distance == 0.0 {
+                return puzzle;
+            }
+            if closest.as_ref().is_none_or(|(best, _)| distance < *best) {
+                closest = Some((distance, puzzle));
+            }
+
+            if options.time_budget.is_some_and(|budget| started.elapsed() >= budget) {
+                break;
+            }
+        }
+        closest.map(|(_, puzzle)| puzzle).unwrap_or_default()
+    }
+
+    // Easy bands need enough givens left for singles to carry the whole
+    // solve; harder techniques mostly show up once most givens are gone.
+    fn cells_to_remove(band: RatingBand) -> usize {
+        if band.max <= Technique::HiddenSingle.score() {
+            40
+        } else if band.min >= Rating::GUESSING_SCORE {
+            64
+        } else {
+            58
+        }
+    }
 
-        Self::remove_cells(&mut grid, cells_to_remove, &mut rng);
+    fn generate_puzzle(cells_to_remove: usize, symmetry: Symmetry, rng: &mut impl Rng) -> Puzzle {
+        let mut grid = Grid::new();
+
+        Self::fill_diagonal_boxes(&mut grid, rng);
+        Solver::solve(&mut grid);
+        let solution = grid.clone();
+
+        Self::remove_cells(&mut grid, cells_to_remove, symmetry, rng);
 
         for row in 0..9 {
             for col in 0..9 {
@@ -27,16 +126,14 @@ impl Generator {
                 }
             }
         }
-        grid
+        Puzzle { grid, solution }
     }
 
-    fn fill_diagonal_boxes(grid: &mut Grid) {
-        let mut rng = rand::thread_rng();
-
+    fn fill_diagonal_boxes(grid: &mut Grid, rng: &mut impl Rng) {
         for box_start in (0..9).step_by(3) {
             let mut numbers: Vec<u8> = (1..=9).collect();
             for i in(1..numbers.len()).rev(){
-                let j = rng.gen_range(0..=i);
+                let j = Self::random_index(rng, i);
                 numbers.swap(i, j);
             }
             let mut idx = 0;
@@ -49,7 +146,16 @@ impl Generator {
         }
     }
 
-    fn remove_cells(grid: &mut Grid, count: usize, rng: &mut impl Rng) {
+    // Draws from a u32 range rather than usize, whose sampling differs between
+    // 32- and 64-bit targets and would give shared seeds different puzzles.
+    fn random_index(rng: &mut impl Rng, max: usize) -> usize {
+        rng.gen_range(0..=max as u32) as usize
+    }
+
+    // Asking for all 81 cells tries every position once, which leaves a
+    // minimal puzzle: a given that couldn't be removed earlier can't be
+    // removed later either, as blanking more cells only adds solutions.
+    fn remove_cells(grid: &mut Grid, count: usize, symmetry: Symmetry, rng: &mut impl Rng) {
         let mut removed = 0;
         let mut attempts = 0;
         let max_attempts = count * 15;
@@ -62,7 +168,7 @@ impl Generator {
         }
 
         for i in (1..positions.len()).rev(){
-            let j = rng.gen_range(0..=i);
+            let j = Self::random_index(rng, i);
             positions.swap(i, j);
         }
 
@@ -71,14 +177,22 @@ impl Generator {
                 break;
             }
 
-            if grid.get(row, col) != 0 {
-                let backup = grid.get(row, col);
-                grid.set(row, col, 0);
-                let mut test_grid = grid.clone();
-                if Solver::solve(&mut test_grid){
-                    removed += 1;
+            // Cells are blanked together with their mirror images so the
+            // givens keep the requested symmetry.
+            let orbit = symmetry.orbit(row, col);
+            if orbit.iter().all(|&(r, c)| grid.get(r, c) != 0) {
+                let backup: Vec<u8> = orbit.iter().map(|&(r, c)| grid.get(r, c)).collect();
+                for &(r, c) in &orbit {
+                    grid.set(r, c, 0);
+                }
+                // Only keep the removal if the puzzle still has a single answer,
+                // otherwise players can enter a valid solution we don't expect.
+                if solver::is_unique(grid) {
+                    removed += orbit.len();
                 } else {
-                    grid.set(row, col, backup);
+                    for (&(r, c), &value) in orbit.iter().zip(&backup) {
+                        grid.set(r, c, value);
+                    }
                 }
             }
 
@@ -90,9 +204,214 @@ impl Generator {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Difficulty {
     Easy,
     Medium,
     Hard,
+    Expert,
+    Diabolical,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 5] = [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Expert,
+        Difficulty::Diabolical,
+    ];
+
+    /// The ratings a puzzle of this difficulty is generated to have.
+    pub fn band(self) -> RatingBand {
+        match self {
+            Difficulty::Easy => RatingBand::exactly(Technique::HiddenSingle),
+            Difficulty::Medium => RatingBand::between(Technique::NakedSingle, Technique::BoxLineReduction),
+            Difficulty::Hard => RatingBand::between(Technique::NakedPair, Technique::NakedTriple),
+            Difficulty::Expert => RatingBand::between(Technique::Swordfish, Technique::XYWing),
+            Difficulty::Diabolical => RatingBand::beyond_logic(),
+        }
+    }
+}
+
+/// Symmetry of the pattern of givens in a generated puzzle.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Symmetry {
+    #[default]
+    None,
+    /// Unchanged by a half turn.
+    Rotational180,
+    /// Unchanged by a quarter turn.
+    Rotational90,
+    /// Mirrored top to bottom.
+    Horizontal,
+    /// Mirrored left to right.
+    Vertical,
+    /// Mirrored across the main diagonal.
+    Diagonal,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 6] = [
+        Symmetry::None,
+        Symmetry::Rotational180,
+        Symmetry::Rotational90,
+        Symmetry::Horizontal,
+        Symmetry::Vertical,
+        Symmetry::Diagonal,
+    ];
+
+    /// Short name used in [`GameCode`]s, empty for no symmetry.
+    pub fn code(self) -> &'static str {
+        match self {
+            Symmetry::None => "",
+            Symmetry::Rotational180 => "rot180",
+            Symmetry::Rotational90 => "rot90",
+            Symmetry::Horizontal => "horiz",
+            Symmetry::Vertical => "vert",
+            Symmetry::Diagonal => "diag",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Symmetry::None => "None",
+            Symmetry::Rotational180 => "180° rotational",
+            Symmetry::Rotational90 => "90° rotational",
+            Symmetry::Horizontal => "Horizontal",
+            Symmetry::Vertical => "Vertical",
+            Symmetry::Diagonal => "Diagonal",
+        }
+    }
+
+    /// The cell and every cell the symmetry maps it to, without duplicates.
+    pub fn orbit(self, row: usize, col: usize) -> Vec<(usize, usize)> {
+        let images = match self {
+            Symmetry::None => vec![],
+            Symmetry::Rotational180 => vec![(8 - row, 8 - col)],
+            Symmetry::Rotational90 => vec![(col, 8 - row), (8 - row, 8 - col), (8 - col, row)],
+            Symmetry::Horizontal => vec![(8 - row, col)],
+            Symmetry::Vertical => vec![(row, 8 - col)],
+            Symmetry::Diagonal => vec![(col, row)],
+        };
+
+        let mut orbit = vec![(row, col)];
+        for cell in images {
+            if !orbit.contains(&cell) {
+                orbit.push(cell);
+            }
+        }
+        orbit
+    }
+}
+
+/// Everything that decides a seeded puzzle, written so players can share
+/// it: the difficulty, the seed, then the symmetry and `min` when they
+/// apply, as in `medium-123456` or `expert-42-rot180-min`. The same code
+/// always gives the same puzzle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GameCode {
+    pub difficulty: Difficulty,
+    pub seed: u64,
+    pub symmetry: Symmetry,
+    pub minimal: bool,
+}
+
+impl GameCode {
+    pub fn new(difficulty: Difficulty, seed: u64, options: &GeneratorOptions) -> Self {
+        Self {
+            difficulty,
+            seed,
+            symmetry: options.symmetry,
+            minimal: options.minimal,
+        }
+    }
+
+    /// The options the puzzle is generated with. Attempts and time budget
+    /// are left at their defaults, so they can't make two players' puzzles
+    /// differ.
+    pub fn options(&self) -> GeneratorOptions {
+        GeneratorOptions {
+            symmetry: self.symmetry,
+            minimal: self.minimal,
+            ..GeneratorOptions::default()
+        }
+    }
+
+    pub fn generate(&self) -> Puzzle {
+        Generator::generate_seeded(self.difficulty, self.seed, &self.options())
+    }
+}
+
+impl fmt::Display for GameCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", format!("{:?}", self.difficulty).to_lowercase(), self.seed)?;
+        if self.symmetry != Symmetry::None {
+            write!(f, "-{}", self.symmetry.code())?;
+        }
+        if self.minimal {
+            write!(f, "-min")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseGameCodeError {
+    /// The code didn't start with a difficulty name.
+    UnknownDifficulty(String),
+    /// The part after the difficulty wasn't a whole number.
+    InvalidSeed(String),
+    /// A part after the seed that is neither a symmetry nor `min`.
+    UnknownOption(String),
+}
+
+impl fmt::Display for ParseGameCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseGameCodeError::UnknownDifficulty(part) => write!(f, "unknown difficulty {part:?}"),
+            ParseGameCodeError::InvalidSeed(part) => write!(f, "{part:?} is not a seed, e.g. 123456"),
+            ParseGameCodeError::UnknownOption(part) => write!(f, "unknown option {part:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseGameCodeError {}
+
+/// Reads what [`GameCode`]'s `Display` writes, ignoring case and
+/// surrounding whitespace.
+impl FromStr for GameCode {
+    type Err = ParseGameCodeError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut parts = text.trim().split('-');
+        let difficulty_part = parts.next().unwrap_or_default();
+        let difficulty = Difficulty::ALL
+            .into_iter()
+            .find(|difficulty| format!("{difficulty:?}").eq_ignore_ascii_case(difficulty_part))
+            .ok_or_else(|| ParseGameCodeError::UnknownDifficulty(difficulty_part.to_owned()))?;
+        let seed_part = parts.next().unwrap_or_default();
+        let seed = seed_part
+            .parse()
+            .map_err(|_| ParseGameCodeError::InvalidSeed(seed_part.to_owned()))?;
+
+        let mut code = GameCode {
+            difficulty,
+            seed,
+            symmetry: Symmetry::None,
+            minimal: false,
+        };
+        for part in parts {
+            if part.eq_ignore_ascii_case("min") {
+                code.minimal = true;
+            } else if let Some(symmetry) = Symmetry::ALL
+                .into_iter()
+                .find(|symmetry| *symmetry != Symmetry::None && symmetry.code().eq_ignore_ascii_case(part)) {
+                code.symmetry = symmetry;
+            } else {
+                return Err(ParseGameCodeError::UnknownOption(part.to_owned()));
+            }
+        }
+        Ok(code)
+    }
 }
@@ -1,7 +1,25 @@
-#[derive(Clone, Debug)]
+use serde::{Deserialize, Serialize};
+
+use super::candidates::CandidateSet;
+
+// Only what can't be derived is saved; masks and counts are rebuilt on load.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(from = "SavedGrid", into = "SavedGrid")]
 pub struct Grid{
     cells: [[u8; 9]; 9],
     fixed: [[bool; 9]; 9],
+    // Digits present in each row, column and box, kept in sync by `set`.
+    row_masks: [CandidateSet; 9],
+    col_masks: [CandidateSet; 9],
+    box_masks: [CandidateSet; 9],
+    // How often each digit occurs per unit (rows, then columns, then boxes),
+    // so a mask bit survives clearing one copy of a duplicated digit.
+    unit_counts: [[u8; 9]; 27],
+    // Digits each cell could still take given its row, column and box,
+    // updated by `set` for the cell and its peers.
+    candidates: [[CandidateSet; 9]; 9],
+    // Pencil marks the player has made in empty cells.
+    notes: [[CandidateSet; 9]; 9],
 }
 
 impl Grid {
@@ -9,22 +27,165 @@ impl Grid {
         Self{
             cells: [[0; 9]; 9],
             fixed: [[false; 9]; 9],
+            row_masks: [CandidateSet::EMPTY; 9],
+            col_masks: [CandidateSet::EMPTY; 9],
+            box_masks: [CandidateSet::EMPTY; 9],
+            unit_counts: [[0; 9]; 27],
+            candidates: [[CandidateSet::ALL; 9]; 9],
+            notes: [[CandidateSet::EMPTY; 9]; 9],
         }
     }
 
+    pub fn box_index(row: usize, col: usize) -> usize {
+        (row / 3) * 3 + col / 3
+    }
+
     pub fn get(&self, row: usize, col: usize) -> u8 {
         self.cells[row][col]
     }
 
     pub fn set(&mut self, row: usize, col: usize, value: u8) {
+        let old = self.cells[row][col];
+        if old == value {
+            return;
+        }
         self.cells[row][col] = value;
+
+        let box_idx = Self::box_index(row, col);
+        if old != 0 {
+            let digit = old as usize - 1;
+            for (mask, unit) in [
+                (&mut self.row_masks[row], row),
+                (&mut self.col_masks[col], 9 + col),
+                (&mut self.box_masks[box_idx], 18 + box_idx),
+            ] {
+                self.unit_counts[unit][digit] -= 1;
+                if self.unit_counts[unit][digit] == 0 {
+                    mask.remove(old);
+                }
+            }
+        }
+        if value != 0 {
+            let digit = value as usize - 1;
+            for (mask, unit) in [
+                (&mut self.row_masks[row], row),
+                (&mut self.col_masks[col], 9 + col),
+                (&mut self.box_masks[box_idx], 18 + box_idx),
+            ] {
+                self.unit_counts[unit][digit] += 1;
+                mask.insert(value);
+            }
+        }
+
+        // Only the two digits involved can change, and only for cells
+        // sharing a unit with this one. Box cells in the same row or column
+        // get visited twice, which is harmless.
+        let (box_row, box_col) = (box_idx / 3 * 3, box_idx % 3 * 3);
+        for i in 0..9 {
+            for (r, c) in [(row, i), (i, col), (box_row + i / 3, box_col + i % 3)] {
+                if old != 0 && !self.is_used(r, c, old) {
+                    self.candidates[r][c].insert(old);
+                }
+                if value != 0 {
+                    self.candidates[r][c].remove(value);
+                }
+            }
+        }
+    }
+
+    fn is_used(&self, row: usize, col: usize, digit: u8) -> bool {
+        self.row_masks[row].contains(digit)
+            || self.col_masks[col].contains(digit)
+            || self.box_masks[Self::box_index(row, col)].contains(digit)
     }
 
+    /// Sets a non-fixed cell. Placing a digit also clears the cell's notes
+    /// and removes the digit from the notes of its peers.
     pub fn set_user(&mut self, row: usize, col: usize, value: u8) {
-    if !self.fixed[row][col] {
-        self.cells[row][col] = value;
+        if !self.fixed[row][col] {
+            self.set(row, col, value);
+            if value != 0 {
+                self.notes[row][col] = CandidateSet::EMPTY;
+                for (peer_row, peer_col) in Self::peers(row, col) {
+                    self.notes[peer_row][peer_col].remove(value);
+                }
+            }
+        }
+    }
+
+    pub fn notes(&self, row: usize, col: usize) -> CandidateSet {
+        self.notes[row][col]
+    }
+
+    pub fn set_notes(&mut self, row: usize, col: usize, notes: CandidateSet) {
+        if !self.fixed[row][col] {
+            self.notes[row][col] = notes;
+        }
+    }
+
+    /// Adds or removes a note. Only empty cells can hold notes.
+    pub fn toggle_note(&mut self, row: usize, col: usize, digit: u8) {
+        if !self.fixed[row][col] && self.cells[row][col] == 0 {
+            self.notes[row][col].toggle(digit);
+        }
+    }
+
+    /// Sets the notes of every empty player cell to all of its candidates.
+    pub fn fill_candidate_notes(&mut self) {
+        for row in 0..9 {
+            for col in 0..9 {
+                if !self.fixed[row][col] && self.cells[row][col] == 0 {
+                    self.notes[row][col] = self.candidates(row, col);
+                }
+            }
+        }
+    }
+
+    /// Undoes the note clean-up of placing `digit` at (`row`, `col`) once it
+    /// is gone again: the cell gets its candidates back if empty, and empty
+    /// peers get `digit` back wherever it fits again.
+    pub fn restore_candidate_notes(&mut self, row: usize, col: usize, digit: u8) {
+        if !self.fixed[row][col] && self.cells[row][col] == 0 {
+            self.notes[row][col] = self.candidates(row, col);
+        }
+        for (peer_row, peer_col) in Self::peers(row, col) {
+            if !self.fixed[peer_row][peer_col]
+                && self.cells[peer_row][peer_col] == 0
+                && self.candidates(peer_row, peer_col).contains(digit)
+            {
+                self.notes[peer_row][peer_col].insert(digit);
+            }
+        }
+    }
+
+    /// The 20 cells sharing a row, column or box with (`row`, `col`).
+    pub fn peers(row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> {
+        let box_idx = Self::box_index(row, col);
+        (0..81)
+            .map(|i| (i / 9, i % 9))
+            .filter(move |&(r, c)| {
+                (r, c) != (row, col) && (r == row || c == col || Self::box_index(r, c) == box_idx)
+            })
+    }
+
+    /// Digits not yet used in the row, column or box of a cell. A filled
+    /// cell's own digit counts as used.
+    pub fn candidates(&self, row: usize, col: usize) -> CandidateSet {
+        self.candidates[row][col]
+    }
+
+    /// Cells of one of the 27 units: rows are 0-8, columns 9-17 and boxes
+    /// 18-26, each listed in row-major order.
+    pub fn unit_cells(unit: usize) -> [(usize, usize); 9] {
+        std::array::from_fn(|i| match unit {
+            0..9 => (unit, i),
+            9..18 => (i, unit - 9),
+            _ => {
+                let box_idx = unit - 18;
+                ((box_idx / 3) * 3 + i / 3, (box_idx % 3) * 3 + i % 3)
+            }
+        })
     }
-}
 
     pub fn is_fixed(&self, row: usize, col: usize) -> bool {
         self.fixed[row][col]
@@ -38,7 +199,8 @@ impl Grid {
         for row in 0..9{
             for col in 0..9{
                 if !self.fixed[row][col]{
-                    self.cells[row][col] = 0;
+                    self.set(row, col, 0);
+                    self.notes[row][col] = CandidateSet::EMPTY;
                 }
             }
         }
@@ -53,6 +215,39 @@ impl Grid {
         true
     }
 
+    /// Cells whose digit also appears elsewhere in their row, column or
+    /// box, flagged as `conflicts[row][col]`. All false for a valid grid.
+    pub fn conflicts(&self) -> [[bool; 9]; 9] {
+        let mut conflicts = [[false; 9]; 9];
+        for (row, flags) in conflicts.iter_mut().enumerate() {
+            for (col, flag) in flags.iter_mut().enumerate() {
+                let value = self.cells[row][col];
+                if value != 0 {
+                    let digit = value as usize - 1;
+                    let box_idx = Self::box_index(row, col);
+                    *flag = [row, 9 + col, 18 + box_idx]
+                        .iter()
+                        .any(|&unit| self.unit_counts[unit][digit] > 1);
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// Filled cells whose digit differs from `solution`, flagged the same
+    /// way as [`Grid::conflicts`]. Unlike conflicts, this also catches wrong
+    /// digits that don't clash with anything yet.
+    pub fn mistakes(&self, solution: &Grid) -> [[bool; 9]; 9] {
+        let mut mistakes = [[false; 9]; 9];
+        for (row, flags) in mistakes.iter_mut().enumerate() {
+            for (col, flag) in flags.iter_mut().enumerate() {
+                let value = self.cells[row][col];
+                *flag = value != 0 && value != solution.cells[row][col];
+            }
+        }
+        mistakes
+    }
+
     fn is_valid_row(&self, row: usize) -> bool {
         let mut seen = [false; 9];
         for col in 0..9 {
@@ -100,6 +295,11 @@ impl Grid {
         true
     }
 
+    /// Number of cells holding a digit.
+    pub fn filled_count(&self) -> usize {
+        self.cells.iter().flatten().filter(|&&value| value != 0).count()
+    }
+
     pub fn is_complete(&self) -> bool {
         for row in 0..9{
             for col in 0..9 {
@@ -112,6 +312,39 @@ impl Grid {
     }
 }
 
+#[derive(Serialize, Deserialize)]
+struct SavedGrid {
+    cells: [[u8; 9]; 9],
+    fixed: [[bool; 9]; 9],
+    notes: [[CandidateSet; 9]; 9],
+}
+
+impl From<SavedGrid> for Grid {
+    fn from(saved: SavedGrid) -> Self {
+        let mut grid = Grid::new();
+        for row in 0..9 {
+            for col in 0..9 {
+                // Out-of-range digits in a hand-edited file are dropped.
+                let value = saved.cells[row][col];
+                grid.set(row, col, if value <= 9 { value } else { 0 });
+                grid.fixed[row][col] = saved.fixed[row][col];
+                grid.notes[row][col] = saved.notes[row][col].intersection(CandidateSet::ALL);
+            }
+        }
+        grid
+    }
+}
+
+impl From<Grid> for SavedGrid {
+    fn from(grid: Grid) -> Self {
+        SavedGrid {
+            cells: grid.cells,
+            fixed: grid.fixed,
+            notes: grid.notes,
+        }
+    }
+}
+
 impl Default for Grid {
     fn default() -> Self {
         Self::new()
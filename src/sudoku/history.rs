@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+
+use super::candidates::CandidateSet;
+use super::grid::Grid;
+
+/// What a player can change about a cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CellState {
+    pub value: u8,
+    pub notes: CandidateSet,
+}
+
+impl CellState {
+    pub fn of(grid: &Grid, row: usize, col: usize) -> Self {
+        Self {
+            value: grid.get(row, col),
+            notes: grid.notes(row, col),
+        }
+    }
+
+    fn apply(self, grid: &mut Grid, row: usize, col: usize) {
+        grid.set(row, col, self.value);
+        grid.set_notes(row, col, self.notes);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CellChange {
+    pub row: usize,
+    pub col: usize,
+    pub before: CellState,
+    pub after: CellState,
+}
+
+/// One player action, such as placing a digit, clearing a cell or editing
+/// notes, with every cell it touched. Automatic note clean-up in peers is
+/// part of the move that caused it.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Move {
+    pub changes: Vec<CellChange>,
+}
+
+impl Move {
+    /// The move that turns `before` into `after`.
+    pub fn between(before: &Grid, after: &Grid) -> Self {
+        let mut changes = Vec::new();
+        for row in 0..9 {
+            for col in 0..9 {
+                let (old, new) = (CellState::of(before, row, col), CellState::of(after, row, col));
+                if old != new {
+                    changes.push(CellChange {
+                        row,
+                        col,
+                        before: old,
+                        after: new,
+                    });
+                }
+            }
+        }
+        Self { changes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// The digit this move wrote into the cell, if it changed the cell's
+    /// value to something other than empty.
+    pub fn placed(&self, row: usize, col: usize) -> Option<u8> {
+        self.changes
+            .iter()
+            .find(|change| change.row == row && change.col == col)
+            .filter(|change| change.before.value != change.after.value && change.after.value != 0)
+            .map(|change| change.after.value)
+    }
+}
+
+/// Unlimited undo/redo of player moves.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct History {
+    undo: Vec<Move>,
+    redo: Vec<Move>,
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a move made by the player. Moves that changed nothing are
+    /// dropped; anything else discards the redo stack.
+    pub fn record(&mut self, mv: Move) {
+        if mv.is_empty() {
+            return;
+        }
+        self.undo.push(mv);
+        self.redo.clear();
+    }
+
+    /// Reverts the last move, returning false if there was none.
+    pub fn undo(&mut self, grid: &mut Grid) -> bool {
+        let Some(mv) = self.undo.pop() else {
+            return false;
+        };
+        for change in mv.changes.iter().rev() {
+            change.before.apply(grid, change.row, change.col);
+        }
+        self.redo.push(mv);
+        true
+    }
+
+    /// Reapplies the last undone move, returning false if there was none.
+    pub fn redo(&mut self, grid: &mut Grid) -> bool {
+        let Some(mv) = self.redo.pop() else {
+            return false;
+        };
+        for change in &mv.changes {
+            change.after.apply(grid, change.row, change.col);
+        }
+        self.undo.push(mv);
+        true
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
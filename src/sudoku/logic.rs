@@ -0,0 +1,615 @@
+use super::candidates::CandidateSet;
+use super::grid::Grid;
+
+/// Solving techniques a human would use, from easiest to hardest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Technique {
+    HiddenSingle,
+    NakedSingle,
+    PointingPair,
+    BoxLineReduction,
+    NakedPair,
+    XWing,
+    HiddenPair,
+    NakedTriple,
+    Swordfish,
+    HiddenTriple,
+    XYWing,
+}
+
+impl Technique {
+    pub const ALL: [Technique; 11] = [
+        Technique::HiddenSingle,
+        Technique::NakedSingle,
+        Technique::PointingPair,
+        Technique::BoxLineReduction,
+        Technique::NakedPair,
+        Technique::XWing,
+        Technique::HiddenPair,
+        Technique::NakedTriple,
+        Technique::Swordfish,
+        Technique::HiddenTriple,
+        Technique::XYWing,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Technique::HiddenSingle => "Hidden Single",
+            Technique::NakedSingle => "Naked Single",
+            Technique::PointingPair => "Pointing Pair",
+            Technique::BoxLineReduction => "Box/Line Reduction",
+            Technique::NakedPair => "Naked Pair",
+            Technique::XWing => "X-Wing",
+            Technique::HiddenPair => "Hidden Pair",
+            Technique::NakedTriple => "Naked Triple",
+            Technique::Swordfish => "Swordfish",
+            Technique::HiddenTriple => "Hidden Triple",
+            Technique::XYWing => "XY-Wing",
+        }
+    }
+
+    /// Difficulty on the Sudoku Explainer scale.
+    pub fn score(self) -> f32 {
+        match self {
+            Technique::HiddenSingle => 1.5,
+            Technique::NakedSingle => 2.3,
+            Technique::PointingPair => 2.6,
+            Technique::BoxLineReduction => 2.8,
+            Technique::NakedPair => 3.0,
+            Technique::XWing => 3.2,
+            Technique::HiddenPair => 3.4,
+            Technique::NakedTriple => 3.6,
+            Technique::Swordfish => 3.8,
+            Technique::HiddenTriple => 4.0,
+            Technique::XYWing => 4.2,
+        }
+    }
+}
+
+/// One logical step: either a digit placed or candidates eliminated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Deduction {
+    pub technique: Technique,
+    /// Cells whose candidates make up the pattern.
+    pub cells: Vec<(usize, usize)>,
+    /// Units the pattern lives in, numbered as in [`Grid::unit_cells`].
+    pub units: Vec<usize>,
+    /// Digits the pattern is about.
+    pub digits: CandidateSet,
+    /// Digit placed as (row, col, digit), for singles.
+    pub placement: Option<(usize, usize, u8)>,
+    /// Candidates removed as (row, col, digit).
+    pub eliminations: Vec<(usize, usize, u8)>,
+}
+
+impl Deduction {
+    /// A sentence for the player on why the step holds, e.g. "Hidden Single:
+    /// 4 can only go in r2c3 within box 1." Cells are named rNcM, counting
+    /// from 1.
+    pub fn explain(&self) -> String {
+        let digits = list(self.digits.iter().map(|digit| digit.to_string()));
+        let cells = list(self.cells.iter().map(|&cell| cell_name(cell)));
+        let units: Vec<String> = self.units.iter().map(|&unit| unit_name(unit)).collect();
+
+        let reason = match self.technique {
+            Technique::HiddenSingle => {
+                format!("{digits} can only go in {cells} within {}", units[0])
+            }
+            Technique::NakedSingle => format!("{digits} is the only candidate left in {cells}"),
+            Technique::PointingPair | Technique::BoxLineReduction => format!(
+                "within {}, {digits} is confined to {}, so it can't be anywhere else in {}",
+                units[0], units[1], units[1]
+            ),
+            Technique::NakedPair | Technique::NakedTriple => format!(
+                "{cells} hold only {digits} between them, so no other cell of {} can",
+                units[0]
+            ),
+            Technique::HiddenPair | Technique::HiddenTriple => format!(
+                "within {}, {digits} only fit in {cells}, so those cells can't hold anything else",
+                units[0]
+            ),
+            Technique::XWing | Technique::Swordfish => {
+                let (bases, covers) = units.split_at(units.len() / 2);
+                let covers = list(covers.iter().cloned());
+                format!(
+                    "in {}, {digits} is confined to {covers}, so it can't be anywhere else in {covers}",
+                    list(bases.iter().cloned()),
+                )
+            }
+            Technique::XYWing => {
+                let [pivot, first, second] = [self.cells[0], self.cells[1], self.cells[2]];
+                format!(
+                    "whatever {} turns out to be, {} or {} is {z}, so no cell seeing both can be {z}",
+                    cell_name(pivot),
+                    cell_name(first),
+                    cell_name(second),
+                    z = self.eliminations[0].2,
+                )
+            }
+        };
+
+        let mut text = format!("{}: {reason}.", self.technique.name());
+        if !self.eliminations.is_empty() {
+            let removed = list(
+                self.eliminations
+                    .iter()
+                    .map(|&(row, col, digit)| format!("{digit} from {}", cell_name((row, col)))),
+            );
+            text += &format!(" Removes {removed}.");
+        }
+        text
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogicOutcome {
+    Solved,
+    /// No technique applies any more; finishing needs guessing (or the
+    /// puzzle has no solution).
+    Stuck,
+}
+
+/// Result of [`LogicalSolver::solve`].
+#[derive(Clone, Debug)]
+pub struct LogicalSolution {
+    /// Every step taken, in order.
+    pub deductions: Vec<Deduction>,
+    /// The grid as far as logic got.
+    pub grid: Grid,
+    pub outcome: LogicOutcome,
+}
+
+/// Solves with human techniques only, keeping pencil-mark style candidates
+/// per cell so eliminations carry over between steps.
+#[derive(Clone, Debug)]
+pub struct LogicalSolver {
+    grid: Grid,
+    candidates: [[CandidateSet; 9]; 9],
+}
+
+impl LogicalSolver {
+    pub fn new(grid: &Grid) -> Self {
+        let mut candidates = [[CandidateSet::EMPTY; 9]; 9];
+        for (row, row_candidates) in candidates.iter_mut().enumerate() {
+            for (col, cell) in row_candidates.iter_mut().enumerate() {
+                if grid.get(row, col) == 0 {
+                    *cell = grid.candidates(row, col);
+                }
+            }
+        }
+        Self {
+            grid: grid.clone(),
+            candidates,
+        }
+    }
+
+    /// Applies deductions until the grid is full or nothing applies.
+    pub fn solve(grid: &Grid) -> LogicalSolution {
+        let mut solver = Self::new(grid);
+        let mut deductions = Vec::new();
+
+        while let Some(deduction) = solver.next_deduction() {
+            solver.apply(&deduction);
+            deductions.push(deduction);
+        }
+
+        let outcome = if solver.grid.is_complete() {
+            LogicOutcome::Solved
+        } else {
+            LogicOutcome::Stuck
+        };
+        LogicalSolution {
+            deductions,
+            grid: solver.grid,
+            outcome,
+        }
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    pub fn candidates(&self, row: usize, col: usize) -> CandidateSet {
+        self.candidates[row][col]
+    }
+
+    /// The easiest deduction available, if any.
+    pub fn next_deduction(&self) -> Option<Deduction> {
+        Technique::ALL
+            .into_iter()
+            .find_map(|technique| self.find(technique))
+    }
+
+    /// What to tell a player who asks for help: the easiest deductions up
+    /// to and including the next placed digit. Steps before the placement
+    /// only eliminate candidates the placement depends on. Ends without a
+    /// placement if logic gets stuck first, and is empty if nothing applies.
+    pub fn steps_to_next_placement(&self) -> Vec<Deduction> {
+        let mut solver = self.clone();
+        let mut steps = Vec::new();
+        while let Some(deduction) = solver.next_deduction() {
+            let placed = deduction.placement.is_some();
+            solver.apply(&deduction);
+            steps.push(deduction);
+            if placed {
+                break;
+            }
+        }
+        steps
+    }
+
+    pub fn apply(&mut self, deduction: &Deduction) {
+        if let Some((row, col, digit)) = deduction.placement {
+            self.grid.set(row, col, digit);
+            self.candidates[row][col] = CandidateSet::EMPTY;
+            for (peer_row, peer_col) in Grid::peers(row, col) {
+                self.candidates[peer_row][peer_col].remove(digit);
+            }
+        }
+        for &(row, col, digit) in &deduction.eliminations {
+            self.candidates[row][col].remove(digit);
+        }
+    }
+
+    fn find(&self, technique: Technique) -> Option<Deduction> {
+        match technique {
+            Technique::HiddenSingle => self.hidden_single(),
+            Technique::NakedSingle => self.naked_single(),
+            Technique::PointingPair => self.pointing(),
+            Technique::BoxLineReduction => self.box_line_reduction(),
+            Technique::NakedPair => self.naked_subset(2, technique),
+            Technique::NakedTriple => self.naked_subset(3, technique),
+            Technique::HiddenPair => self.hidden_subset(2, technique),
+            Technique::HiddenTriple => self.hidden_subset(3, technique),
+            Technique::XWing => self.fish(2, technique),
+            Technique::Swordfish => self.fish(3, technique),
+            Technique::XYWing => self.xy_wing(),
+        }
+    }
+
+    fn naked_single(&self) -> Option<Deduction> {
+        for row in 0..9 {
+            for col in 0..9 {
+                if let Some(digit) = self.candidates[row][col].only() {
+                    return Some(Deduction {
+                        technique: Technique::NakedSingle,
+                        cells: vec![(row, col)],
+                        units: Vec::new(),
+                        digits: CandidateSet::single(digit),
+                        placement: Some((row, col, digit)),
+                        eliminations: Vec::new(),
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    fn hidden_single(&self) -> Option<Deduction> {
+        // Boxes first, as those are the easiest to spot.
+        for unit in (18..27).chain(0..18) {
+            for digit in 1..=9 {
+                let spots = self.spots(unit, digit);
+                if let [(row, col)] = spots[..] {
+                    return Some(Deduction {
+                        technique: Technique::HiddenSingle,
+                        cells: vec![(row, col)],
+                        units: vec![unit],
+                        digits: CandidateSet::single(digit),
+                        placement: Some((row, col, digit)),
+                        eliminations: Vec::new(),
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// A digit confined to one row or column within a box can't appear in
+    /// the rest of that line.
+    fn pointing(&self) -> Option<Deduction> {
+        for box_unit in 18..27 {
+            for digit in 1..=9 {
+                let spots = self.spots(box_unit, digit);
+                if spots.len() < 2 {
+                    continue;
+                }
+                for line in [spots[0].0, 9 + spots[0].1] {
+                    if !spots.iter().all(|&cell| in_unit(cell, line)) {
+                        continue;
+                    }
+                    let eliminations = self.eliminate(
+                        Grid::unit_cells(line).into_iter().filter(|&cell| !in_unit(cell, box_unit)),
+                        CandidateSet::single(digit),
+                    );
+                    if !eliminations.is_empty() {
+                        return Some(Deduction {
+                            technique: Technique::PointingPair,
+                            cells: spots,
+                            units: vec![box_unit, line],
+                            digits: CandidateSet::single(digit),
+                            placement: None,
+                            eliminations,
+                        });
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// A digit confined to one box within a row or column can't appear in
+    /// the rest of that box.
+    fn box_line_reduction(&self) -> Option<Deduction> {
+        for line in 0..18 {
+            for digit in 1..=9 {
+                let spots = self.spots(line, digit);
+                if spots.len() < 2 {
+                    continue;
+                }
+                let box_unit = 18 + Grid::box_index(spots[0].0, spots[0].1);
+                if !spots.iter().all(|&cell| in_unit(cell, box_unit)) {
+                    continue;
+                }
+                let eliminations = self.eliminate(
+                    Grid::unit_cells(box_unit).into_iter().filter(|&cell| !in_unit(cell, line)),
+                    CandidateSet::single(digit),
+                );
+                if !eliminations.is_empty() {
+                    return Some(Deduction {
+                        technique: Technique::BoxLineReduction,
+                        cells: spots,
+                        units: vec![line, box_unit],
+                        digits: CandidateSet::single(digit),
+                        placement: None,
+                        eliminations,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// `size` cells in a unit holding only `size` digits between them take
+    /// those digits away from the rest of the unit.
+    fn naked_subset(&self, size: usize, technique: Technique) -> Option<Deduction> {
+        for unit in 0..27 {
+            let open: Vec<(usize, usize)> = Grid::unit_cells(unit)
+                .into_iter()
+                .filter(|&(row, col)| (2..=size).contains(&self.candidates[row][col].len()))
+                .collect();
+
+            for subset in combinations(&open, size) {
+                let digits = subset
+                    .iter()
+                    .fold(CandidateSet::EMPTY, |acc, &(row, col)| acc.union(self.candidates[row][col]));
+                if digits.len() != size {
+                    continue;
+                }
+                let eliminations = self.eliminate(
+                    Grid::unit_cells(unit).into_iter().filter(|cell| !subset.contains(cell)),
+                    digits,
+                );
+                if !eliminations.is_empty() {
+                    return Some(Deduction {
+                        technique,
+                        cells: subset,
+                        units: vec![unit],
+                        digits,
+                        placement: None,
+                        eliminations,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// `size` digits confined to the same `size` cells of a unit leave no
+    /// room for other digits in those cells.
+    fn hidden_subset(&self, size: usize, technique: Technique) -> Option<Deduction> {
+        for unit in 0..27 {
+            let open_digits: Vec<u8> = (1..=9)
+                .filter(|&digit| (2..=size).contains(&self.spots(unit, digit).len()))
+                .collect();
+
+            for subset in combinations(&open_digits, size) {
+                let digits = subset
+                    .iter()
+                    .fold(CandidateSet::EMPTY, |mut acc, &digit| {
+                        acc.insert(digit);
+                        acc
+                    });
+                let cells: Vec<(usize, usize)> = Grid::unit_cells(unit)
+                    .into_iter()
+                    .filter(|&(row, col)| !self.candidates[row][col].intersection(digits).is_empty())
+                    .collect();
+                if cells.len() != size {
+                    continue;
+                }
+                let eliminations = self.eliminate(cells.iter().copied(), CandidateSet::ALL.difference(digits));
+                if !eliminations.is_empty() {
+                    return Some(Deduction {
+                        technique,
+                        cells,
+                        units: vec![unit],
+                        digits,
+                        placement: None,
+                        eliminations,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// X-Wing (`size` 2) and Swordfish (`size` 3): if a digit's spots in
+    /// `size` rows all fall in the same `size` columns, it can be removed from
+    /// the rest of those columns; likewise with rows and columns swapped.
+    fn fish(&self, size: usize, technique: Technique) -> Option<Deduction> {
+        for (bases, covers) in [(0..9, 9..18), (9..18, 0..9)] {
+            for digit in 1..=9 {
+                let candidates: Vec<usize> = bases
+                    .clone()
+                    .filter(|&base| (2..=size).contains(&self.spots(base, digit).len()))
+                    .collect();
+
+                for base_set in combinations(&candidates, size) {
+                    let corners: Vec<(usize, usize)> = base_set
+                        .iter()
+                        .flat_map(|&base| self.spots(base, digit))
+                        .collect();
+                    let cover_set: Vec<usize> = covers
+                        .clone()
+                        .filter(|&cover| corners.iter().any(|&cell| in_unit(cell, cover)))
+                        .collect();
+                    if cover_set.len() != size {
+                        continue;
+                    }
+
+                    let eliminations = self.eliminate(
+                        cover_set
+                            .iter()
+                            .flat_map(|&cover| Grid::unit_cells(cover))
+                            .filter(|cell| !base_set.iter().any(|&base| in_unit(*cell, base))),
+                        CandidateSet::single(digit),
+                    );
+                    if !eliminations.is_empty() {
+                        return Some(Deduction {
+                            technique,
+                            cells: corners,
+                            units: base_set.into_iter().chain(cover_set).collect(),
+                            digits: CandidateSet::single(digit),
+                            placement: None,
+                            eliminations,
+                        });
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// A pivot {x, y} seeing pincers {x, z} and {y, z}: whichever digit the
+    /// pivot takes, one pincer is z, so cells seeing both pincers can't be z.
+    fn xy_wing(&self) -> Option<Deduction> {
+        let pairs: Vec<(usize, usize)> = (0..81)
+            .map(|i| (i / 9, i % 9))
+            .filter(|&(row, col)| self.candidates[row][col].len() == 2)
+            .collect();
+
+        for &pivot in &pairs {
+            let pivot_digits = self.candidates[pivot.0][pivot.1];
+            let wings: Vec<(usize, usize)> = pairs
+                .iter()
+                .copied()
+                .filter(|&cell| sees(pivot, cell))
+                .filter(|&(row, col)| self.candidates[row][col].intersection(pivot_digits).len() == 1)
+                .collect();
+
+            for (i, &first) in wings.iter().enumerate() {
+                for &second in &wings[i + 1..] {
+                    let first_digits = self.candidates[first.0][first.1];
+                    let second_digits = self.candidates[second.0][second.1];
+                    let shared = first_digits.intersection(second_digits);
+                    let Some(z) = shared.only() else {
+                        continue;
+                    };
+                    if pivot_digits.contains(z)
+                        || first_digits.union(second_digits).difference(shared) != pivot_digits
+                    {
+                        continue;
+                    }
+
+                    let eliminations = self.eliminate(
+                        Grid::peers(first.0, first.1).filter(|&cell| cell != second && sees(cell, second)),
+                        CandidateSet::single(z),
+                    );
+                    if !eliminations.is_empty() {
+                        return Some(Deduction {
+                            technique: Technique::XYWing,
+                            cells: vec![pivot, first, second],
+                            units: Vec::new(),
+                            digits: pivot_digits.union(shared),
+                            placement: None,
+                            eliminations,
+                        });
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Empty cells of `unit` that still have `digit` as a candidate.
+    fn spots(&self, unit: usize, digit: u8) -> Vec<(usize, usize)> {
+        Grid::unit_cells(unit)
+            .into_iter()
+            .filter(|&(row, col)| self.candidates[row][col].contains(digit))
+            .collect()
+    }
+
+    /// Which of `digits` can be removed from `cells`.
+    fn eliminate(
+        &self,
+        cells: impl Iterator<Item = (usize, usize)>,
+        digits: CandidateSet,
+    ) -> Vec<(usize, usize, u8)> {
+        cells
+            .flat_map(|(row, col)| {
+                self.candidates[row][col]
+                    .intersection(digits)
+                    .iter()
+                    .map(move |digit| (row, col, digit))
+            })
+            .collect()
+    }
+}
+
+fn cell_name((row, col): (usize, usize)) -> String {
+    format!("r{}c{}", row + 1, col + 1)
+}
+
+fn unit_name(unit: usize) -> String {
+    match unit {
+        0..9 => format!("row {}", unit + 1),
+        9..18 => format!("column {}", unit - 8),
+        _ => format!("box {}", unit - 17),
+    }
+}
+
+// "a", "a and b", "a, b and c".
+fn list(items: impl Iterator<Item = String>) -> String {
+    let items: Vec<String> = items.collect();
+    match items.split_last() {
+        None => String::new(),
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} and {last}", rest.join(", ")),
+    }
+}
+
+fn in_unit((row, col): (usize, usize), unit: usize) -> bool {
+    match unit {
+        0..9 => row == unit,
+        9..18 => col == unit - 9,
+        _ => Grid::box_index(row, col) == unit - 18,
+    }
+}
+
+fn sees(a: (usize, usize), b: (usize, usize)) -> bool {
+    a != b && (a.0 == b.0 || a.1 == b.1 || Grid::box_index(a.0, a.1) == Grid::box_index(b.0, b.1))
+}
+
+fn combinations<T: Copy>(items: &[T], size: usize) -> Vec<Vec<T>> {
+    if size == 0 {
+        return vec![Vec::new()];
+    }
+    let mut result = Vec::new();
+    for (i, &item) in items.iter().enumerate() {
+        for mut rest in combinations(&items[i + 1..], size - 1) {
+            rest.insert(0, item);
+            result.push(rest);
+        }
+    }
+    result
+}
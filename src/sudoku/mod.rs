@@ -1,7 +1,19 @@
+pub mod candidates;
+pub mod dlx;
 pub mod grid;
+pub mod history;
 pub mod generator;
+pub mod logic;
+pub mod propagate;
+pub mod rating;
 pub mod solver;
+pub mod text;
 
+pub use candidates::CandidateSet;
 pub use grid::Grid;
-pub use generator::Generator;
-pub use solver::Solver;
\ No newline at end of file
+pub use generator::{GameCode, Generator, Puzzle};
+pub use history::{History, Move};
+pub use logic::{Deduction, LogicalSolver, Technique};
+pub use rating::{Rating, RatingBand};
+pub use solver::{SolveStats, Solver, SolverBackend, SolverKind};
+pub use text::ParseGridError;
@@ -0,0 +1,161 @@
+use super::grid::Grid;
+use super::solver::{SolveStats, SolverBackend};
+
+/// Constraint-propagation solver.
+///
+/// Fills naked and hidden singles until nothing changes, then guesses on the
+/// empty cell with the fewest candidates (minimum remaining values) and
+/// repeats, undoing the guess if it leads to a contradiction.
+pub struct Propagating;
+
+impl SolverBackend for Propagating {
+    fn name(&self) -> &'static str {
+        "Constraint Propagation"
+    }
+
+    fn solve_with_stats(&self, grid: &mut Grid, stats: &mut SolveStats) -> bool {
+        if !grid.is_valid() {
+            return false;
+        }
+
+        let mut search = Search::new(1);
+        search.run(grid.clone(), 1);
+        *stats = search.stats;
+
+        match search.first_solution {
+            Some(solution) => {
+                for row in 0..9 {
+                    for col in 0..9 {
+                        grid.set(row, col, solution.get(row, col));
+                    }
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn count_solutions(&self, grid: &Grid, limit: usize) -> usize {
+        if limit == 0 || !grid.is_valid() {
+            return 0;
+        }
+
+        let mut search = Search::new(limit);
+        search.run(grid.clone(), 1);
+        search.count
+    }
+}
+
+/// Fills naked and hidden singles until none are left. Returns false if the
+/// grid turns out to be contradictory.
+fn propagate(grid: &mut Grid) -> bool {
+    loop {
+        let mut changed = false;
+
+        for row in 0..9 {
+            for col in 0..9 {
+                if grid.get(row, col) != 0 {
+                    continue;
+                }
+                let candidates = grid.candidates(row, col);
+                if candidates.is_empty() {
+                    return false;
+                }
+                if let Some(digit) = candidates.only() {
+                    grid.set(row, col, digit);
+                    changed = true;
+                }
+            }
+        }
+
+        for unit in 0..27 {
+            let cells = Grid::unit_cells(unit);
+            for digit in 1..=9 {
+                if cells.iter().any(|&(row, col)| grid.get(row, col) == digit) {
+                    continue;
+                }
+
+                let mut spots = cells
+                    .iter()
+                    .filter(|&&(row, col)| grid.get(row, col) == 0 && grid.candidates(row, col).contains(digit));
+                match (spots.next(), spots.next()) {
+                    (None, _) => return false,
+                    (Some(&(row, col)), None) => {
+                        grid.set(row, col, digit);
+                        changed = true;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if !changed {
+            return true;
+        }
+    }
+}
+
+/// The empty cell with the fewest candidates, if any cell is empty.
+fn most_constrained_cell(grid: &Grid) -> Option<(usize, usize)> {
+    let mut best: Option<((usize, usize), usize)> = None;
+    for row in 0..9 {
+        for col in 0..9 {
+            if grid.get(row, col) != 0 {
+                continue;
+            }
+            let count = grid.candidates(row, col).len();
+            if best.is_none_or(|(_, best_count)| count < best_count) {
+                best = Some(((row, col), count));
+                if count <= 2 {
+                    return Some((row, col));
+                }
+            }
+        }
+    }
+    best.map(|(cell, _)| cell)
+}
+
+struct Search {
+    limit: usize,
+    count: usize,
+    first_solution: Option<Grid>,
+    stats: SolveStats,
+}
+
+impl Search {
+    fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            count: 0,
+            first_solution: None,
+            stats: SolveStats::default(),
+        }
+    }
+
+    /// Returns true once `limit` solutions have been found.
+    fn run(&mut self, mut grid: Grid, depth: usize) -> bool {
+        if !propagate(&mut grid) {
+            return false;
+        }
+
+        let Some((row, col)) = most_constrained_cell(&grid) else {
+            self.count += 1;
+            if self.first_solution.is_none() {
+                self.first_solution = Some(grid);
+            }
+            return self.count >= self.limit;
+        };
+
+        for digit in grid.candidates(row, col).iter() {
+            let mut guess = grid.clone();
+            guess.set(row, col, digit);
+            self.stats.enter(depth);
+
+            if self.run(guess, depth + 1) {
+                return true;
+            }
+            self.stats.backtracks += 1;
+        }
+        false
+    }
+}
@@ -0,0 +1,95 @@
+use std::fmt;
+
+use super::grid::Grid;
+use super::logic::{LogicOutcome, LogicalSolver, Technique};
+
+/// How hard a puzzle is for a human, judged by the hardest technique the
+/// logical solver needs, in the spirit of Sudoku Explainer (SE) ratings.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rating {
+    /// SE-style score of the hardest technique used.
+    pub score: f32,
+    /// Hardest technique used, `None` if the grid needed no steps at all.
+    pub hardest: Option<Technique>,
+    /// True if logic alone got stuck, so the puzzle needs guessing.
+    pub needs_guessing: bool,
+}
+
+impl Rating {
+    /// Score given to puzzles that need more than the implemented techniques.
+    pub const GUESSING_SCORE: f32 = 5.0;
+
+    pub fn of(grid: &Grid) -> Self {
+        let result = LogicalSolver::solve(grid);
+        let hardest = result.deductions.iter().map(|d| d.technique).max();
+        let needs_guessing = result.outcome == LogicOutcome::Stuck;
+
+        let score = if needs_guessing {
+            Self::GUESSING_SCORE
+        } else {
+            hardest.map_or(0.0, Technique::score)
+        };
+        Self {
+            score,
+            hardest,
+            needs_guessing,
+        }
+    }
+}
+
+impl fmt::Display for Rating {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.needs_guessing {
+            write!(f, "{:.1}+ (needs guessing)", self.score)
+        } else if let Some(technique) = self.hardest {
+            write!(f, "{:.1} ({})", self.score, technique.name())
+        } else {
+            write!(f, "{:.1}", self.score)
+        }
+    }
+}
+
+/// A range of ratings a generated puzzle should fall into, inclusive.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RatingBand {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl RatingBand {
+    /// Puzzles whose hardest step is between `easiest` and `hardest`.
+    pub fn between(easiest: Technique, hardest: Technique) -> Self {
+        Self {
+            min: easiest.score(),
+            max: hardest.score(),
+        }
+    }
+
+    /// Puzzles that need `technique` but nothing harder.
+    pub fn exactly(technique: Technique) -> Self {
+        Self::between(technique, technique)
+    }
+
+    /// Puzzles logic alone can't finish.
+    pub fn beyond_logic() -> Self {
+        Self {
+            min: Rating::GUESSING_SCORE,
+            max: Rating::GUESSING_SCORE,
+        }
+    }
+
+    pub fn contains(&self, rating: &Rating) -> bool {
+        self.distance(rating) == 0.0
+    }
+
+    /// How far a rating's score is outside the band, 0 if inside.
+    pub fn distance(&self, rating: &Rating) -> f32 {
+        if rating.score < self.min {
+            self.min - rating.score
+        } else if rating.score > self.max {
+            rating.score - self.max
+        } else {
+            0.0
+        }
+    }
+}
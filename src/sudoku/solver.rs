@@ -1,13 +1,113 @@
+use serde::{Deserialize, Serialize};
+
+use super::dlx::Dlx;
 use super::grid::Grid;
+use super::propagate::Propagating;
+
+/// A solving algorithm that can be swapped in wherever a grid needs solving.
+pub trait SolverBackend {
+    fn name(&self) -> &'static str;
+
+    /// Fills in `grid`, returning false if it has no solution.
+    fn solve(&self, grid: &mut Grid) -> bool {
+        self.solve_with_stats(grid, &mut SolveStats::default())
+    }
+
+    /// Like `solve`, recording how much searching it took into `stats`.
+    fn solve_with_stats(&self, grid: &mut Grid, stats: &mut SolveStats) -> bool;
+
+    /// Counts the solutions of `grid` without modifying it, stopping once
+    /// `limit` have been found.
+    fn count_solutions(&self, grid: &Grid, limit: usize) -> usize;
+}
+
+/// Whether `grid` has exactly one solution. Puzzles checked this way are
+/// often sparse, such as pasted ones or those late in generation, and row-major
+/// backtracking slows down badly on those, so this uses Dancing Links.
+pub fn is_unique(grid: &Grid) -> bool {
+    Dlx.count_solutions(grid, 2) == 1
+}
+
+/// Search effort of a single solve, for comparing backends.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SolveStats {
+    /// Tentative placements made while searching.
+    pub nodes: u64,
+    /// Placements that led to a dead end and were undone.
+    pub backtracks: u64,
+    /// Most placements on the search stack at once.
+    pub max_depth: usize,
+}
+
+impl SolveStats {
+    pub(crate) fn enter(&mut self, depth: usize) {
+        self.nodes += 1;
+        self.max_depth = self.max_depth.max(depth);
+    }
+}
+
+/// The available solver backends.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SolverKind {
+    Backtracking,
+    /// The default, as the app solves on the UI thread: row-major
+    /// backtracking can take seconds on sparse puzzles built against it.
+    #[default]
+    DancingLinks,
+    Propagating,
+}
+
+impl SolverKind {
+    pub const ALL: [SolverKind; 3] = [
+        SolverKind::Backtracking,
+        SolverKind::DancingLinks,
+        SolverKind::Propagating,
+    ];
+
+    pub fn backend(self) -> &'static dyn SolverBackend {
+        match self {
+            SolverKind::Backtracking => &Backtracking,
+            SolverKind::DancingLinks => &Dlx,
+            SolverKind::Propagating => &Propagating,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        self.backend().name()
+    }
+}
+
+/// Row-major backtracking, i.e. [`Solver`] behind the [`SolverBackend`] trait.
+pub struct Backtracking;
+
+impl SolverBackend for Backtracking {
+    fn name(&self) -> &'static str {
+        "Backtracking"
+    }
+
+    fn solve_with_stats(&self, grid: &mut Grid, stats: &mut SolveStats) -> bool {
+        grid.is_valid() && Solver::backtrack(grid, 0, 0, 1, stats)
+    }
+
+    fn count_solutions(&self, grid: &Grid, limit: usize) -> usize {
+        Solver::count_solutions(grid, limit)
+    }
+}
 
 pub struct Solver;
 
 impl Solver {
     pub fn solve(grid: &mut Grid) -> bool {
-        Self::backtrack(grid, 0, 0)
+        Self::backtrack(grid, 0, 0, 1, &mut SolveStats::default())
     }
 
-    fn backtrack(grid: &mut Grid, mut row: usize, mut col: usize) -> bool {
+    fn backtrack(
+        grid: &mut Grid,
+        mut row: usize,
+        mut col: usize,
+        depth: usize,
+        stats: &mut SolveStats,
+    ) -> bool {
         loop{
             if row >= 9{
                 return true;
@@ -24,27 +124,44 @@ impl Solver {
             }
         }
 
-        for num in 1..=9{
-            if Self::is_safe(grid, row, col, num){
-                grid.set(row, col, num);
-
-                let (next_row, next_col) = if col == 8 {
-                    (row + 1, 0)
-                } else {
-                    (row, col + 1)
-                };
+        for num in grid.candidates(row, col).iter() {
+            grid.set(row, col, num);
+            stats.enter(depth);
 
-                if Self::backtrack(grid, next_row, next_col){
-                    return true;
-                }
+            let (next_row, next_col) = if col == 8 {
+                (row + 1, 0)
+            } else {
+                (row, col + 1)
+            };
 
-                grid.set(row, col, 0);
+            if Self::backtrack(grid, next_row, next_col, depth + 1, stats){
+                return true;
             }
+
+            grid.set(row, col, 0);
+            stats.backtracks += 1;
         }
 
         false
     }
 
+    /// Returns true if the grid has exactly one solution.
+    pub fn has_unique_solution(grid: &Grid) -> bool {
+        Self::count_solutions(grid, 2) == 1
+    }
+
+    /// Counts the solutions of `grid` without modifying it, stopping once
+    /// `limit` have been found.
+    pub fn count_solutions(grid: &Grid, limit: usize) -> usize {
+        Self::solutions(grid).take(limit).count()
+    }
+
+    /// Lazily yields every solution of `grid`, in the same order `solve`
+    /// would find them. A grid with conflicting givens yields nothing.
+    pub fn solutions(grid: &Grid) -> Solutions {
+        Solutions::new(grid)
+    }
+
     pub fn find_next_empty(grid: &Grid, start_row: usize, start_col: usize) -> Option<(usize, usize)> {
         let mut row = start_row;
         let mut col = start_col;
@@ -67,13 +184,7 @@ impl Solver {
     }
 
     pub fn get_valid_numbers(grid: &Grid, row: usize, col: usize) -> Vec<u8> {
-        let mut valid = Vec::new();
-        for num in 1..=9 {
-            if Self::is_safe(grid, row, col, num){
-                valid.push(num);
-            }
-        }
-        valid
+        grid.candidates(row, col).iter().collect()
     }
 
     pub fn is_safe_static(grid: &Grid, row: usize, col: usize, num: u8) -> bool{
@@ -81,27 +192,61 @@ impl Solver {
     }
 
     fn is_safe(grid: &Grid, row: usize, col: usize, num: u8) -> bool {
-        for c in 0..9{
-            if grid.get(row, c) == num{
-                return false;
+        grid.candidates(row, col).contains(num)
+    }
+}
+/// Iterator over all solutions of a grid, see [`Solver::solutions`].
+pub struct Solutions {
+    grid: Grid,
+    // Empty cells currently being filled, with the next number to try.
+    stack: Vec<(usize, usize, u8)>,
+    // Set when the starting grid is already full and valid.
+    complete: Option<Grid>,
+}
+
+impl Solutions {
+    fn new(grid: &Grid) -> Self {
+        let mut solutions = Self {
+            grid: grid.clone(),
+            stack: Vec::new(),
+            complete: None,
+        };
+
+        if grid.is_valid() {
+            match Solver::find_next_empty(grid, 0, 0) {
+                Some((row, col)) => solutions.stack.push((row, col, 1)),
+                None => solutions.complete = Some(grid.clone()),
             }
         }
+        solutions
+    }
+}
 
-        for r in 0..9{
-            if grid.get(r, col) == num {
-                return false;
-            }
+impl Iterator for Solutions {
+    type Item = Grid;
+
+    fn next(&mut self) -> Option<Grid> {
+        if let Some(grid) = self.complete.take() {
+            return Some(grid);
         }
 
-        let box_row = (row / 3) * 3;
-        let box_col = (col / 3) * 3;
-        for r in box_row..box_row + 3{
-            for c in box_col..box_col + 3{
-                if grid.get(r, c) == num {
-                    return false;
-                }
+        while let Some((row, col, next_num)) = self.stack.last_mut() {
+            let (row, col) = (*row, *col);
+            self.grid.set(row, col, 0);
+
+            let candidates = self.grid.candidates(row, col);
+            let Some(num) = (*next_num..=9).find(|&num| candidates.contains(num)) else {
+                self.stack.pop();
+                continue;
+            };
+            *next_num = num + 1;
+            self.grid.set(row, col, num);
+
+            match Solver::find_next_empty(&self.grid, row, col) {
+                Some((next_row, next_col)) => self.stack.push((next_row, next_col, 1)),
+                None => return Some(self.grid.clone()),
             }
         }
-        true
+        None
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,98 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::grid::Grid;
+
+impl Grid {
+    /// The one-line format: 81 characters in reading order, `.` for blanks.
+    pub fn to_line(&self) -> String {
+        (0..81).map(|i| cell_char(self.get(i / 9, i % 9))).collect()
+    }
+
+    /// Nine lines of digits with `|` and `-` between boxes and `.` for
+    /// blanks, for reading by eye. Parsing reads it back.
+    pub fn to_pretty(&self) -> String {
+        let mut text = String::new();
+        for row in 0..9 {
+            if row > 0 && row % 3 == 0 {
+                text += "------+-------+------\n";
+            }
+            for col in 0..9 {
+                if col > 0 {
+                    text += if col % 3 == 0 { " | " } else { " " };
+                }
+                text.push(cell_char(self.get(row, col)));
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+fn cell_char(value: u8) -> char {
+    if value == 0 { '.' } else { char::from(b'0' + value) }
+}
+
+/// Why text couldn't be read as a grid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseGridError {
+    /// The text held this many cells instead of 81.
+    WrongLength(usize),
+    /// A character that is neither a cell nor grid art, at a 1-based line
+    /// and column.
+    InvalidCharacter { character: char, line: usize, column: usize },
+}
+
+impl fmt::Display for ParseGridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseGridError::WrongLength(cells) => {
+                write!(f, "expected 81 cells, found {cells}")
+            }
+            ParseGridError::InvalidCharacter { character, line, column } => {
+                write!(f, "unexpected {character:?} at line {line}, column {column}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseGridError {}
+
+/// Reads the usual one-line format: 81 cells in reading order, digits for
+/// givens and `0` or `.` for blanks. Whitespace and the `|`, `-` and `+`
+/// of ASCII grid art are skipped, so multi-line layouts work too. Givens
+/// come back fixed.
+impl FromStr for Grid {
+    type Err = ParseGridError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut cells = Vec::with_capacity(81);
+        for (line, text_line) in text.lines().enumerate() {
+            for (column, character) in text_line.chars().enumerate() {
+                match character {
+                    '1'..='9' => cells.push(character as u8 - b'0'),
+                    '0' | '.' => cells.push(0),
+                    '|' | '-' | '+' => {}
+                    _ if character.is_whitespace() => {}
+                    _ => {
+                        return Err(ParseGridError::InvalidCharacter {
+                            character,
+                            line: line + 1,
+                            column: column + 1,
+                        });
+                    }
+                }
+            }
+        }
+        if cells.len() != 81 {
+            return Err(ParseGridError::WrongLength(cells.len()));
+        }
+
+        let mut grid = Grid::new();
+        for (i, &value) in cells.iter().enumerate() {
+            grid.set(i / 9, i % 9, value);
+            grid.set_fixed(i / 9, i % 9, value != 0);
+        }
+        Ok(grid)
+    }
+}
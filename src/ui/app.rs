@@ -1,7 +1,43 @@
-use std::{cell, io::empty, time::{Duration, Instant}};
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
 
 use eframe::egui;
-use crate::sudoku::{Generator, Grid, Solver, generator::Difficulty};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::timer::{Stopwatch, format_date, format_duration};
+use crate::sudoku::{
+    CandidateSet, Deduction, GameCode, Grid, History, LogicalSolver, Move, Rating, SolveStats, Solver, SolverBackend, SolverKind,
+    dlx::Dlx,
+    generator::{Difficulty, GeneratorOptions, Symmetry},
+    solver,
+};
+use super::stats::{FinishedGame, GameStats, Outcome, STATS_FILE};
+use super::save::{CURRENT_SLOT_KEY, GAME_KEY, LIBRARY_KEY, Library, SETTINGS_KEY, SavedGame, Settings};
+
+/// Name the window is created under, which also picks where eframe keeps its
+/// storage.
+pub const APP_NAME: &str = "Sudoku";
+
+/// Takes every Tab key event out of `raw_input`, returning whether Tab was
+/// pressed. egui would otherwise turn Tab into moving keyboard focus between
+/// widgets before the app sees it, and the focused button then takes the
+/// next Enter or Space.
+pub fn take_tab_presses(raw_input: &mut egui::RawInput) -> bool {
+    let mut pressed_tab = false;
+    raw_input.events.retain(|event| match event {
+        egui::Event::Key { key: egui::Key::Tab, pressed, .. } => {
+            pressed_tab |= *pressed;
+            false
+        }
+        _ => true,
+    });
+    pressed_tab
+}
+
+// Side of one board cell in points. The pause cover uses it too, so it hides
+// exactly the board.
+const CELL_SIZE: f32 = 50.0;
 
 pub struct SudokuApp{
     grid: Grid,
@@ -11,6 +47,119 @@ pub struct SudokuApp{
     solver_state: Option<SolverState>,
     last_step_time: Instant,
     input_mode: bool,
+    notes_mode: bool,
+    auto_candidates: bool,
+    show_conflicts: bool,
+    history: History,
+    // The answer to the current puzzle, `None` if it has no unique one.
+    solution: Option<Grid>,
+    // Preferred mistake rules, which new games start with.
+    mistake_mode: MistakeMode,
+    mistake_limit: u32,
+    // Rules of the current game, kept apart from the preference so changing
+    // it can't end the game or bring a lost one back.
+    rules: MistakeRules,
+    // The current game ran out of mistakes. Latched, so it stays lost.
+    lost: bool,
+    mistakes: u32,
+    // Set by "Check" in on-demand mode, until the next edit.
+    checked: bool,
+    hint: Option<Hint>,
+    hints_used: u32,
+    // Time spent playing the current game, stopped while paused.
+    clock: Stopwatch,
+    paused: bool,
+    solved_by_solver: bool,
+    puzzle_notice: Option<String>,
+    solver_kind: SolverKind,
+    last_solve_stats: Option<(SolverKind, SolveStats)>,
+    rating: Option<Rating>,
+    // What the current game was generated from, `None` for custom puzzles.
+    game: Option<(Difficulty, GeneratorOptions, u64)>,
+    // Game code typed into, or shown in, the code field.
+    code_input: String,
+    generator_options: GeneratorOptions,
+    library: Library,
+    // Slot the game in progress is kept in sync with, if any.
+    current_slot: Option<String>,
+    show_library: bool,
+    slot_name: String,
+    // Slot being renamed and the name typed so far.
+    renaming: Option<(String, String)>,
+    stats: GameStats,
+    // Where `stats` is kept, `None` when there is nowhere to write it.
+    stats_path: Option<PathBuf>,
+    // Whether the current game, once finished, went into `stats`.
+    recorded: bool,
+    // Whether the player changed the board in the current game.
+    moves_made: bool,
+    show_stats: bool,
+    // Copy buttons use the ASCII grid rather than the one-line format.
+    copy_pretty: bool,
+    // Tab pressed this frame, taken out of egui's input by `raw_input_hook`.
+    tab_pressed: bool,
+}
+
+/// How wrong entries, judged against the stored solution, are reported.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MistakeMode {
+    #[default]
+    Off,
+    /// Flag wrong digits as soon as they are placed.
+    Immediate,
+    /// Like `Immediate`, and the game ends after `mistake_limit` of them.
+    Limited,
+    /// Flag wrong digits only when the player asks.
+    OnDemand,
+}
+
+impl MistakeMode {
+    const ALL: [MistakeMode; 4] = [
+        MistakeMode::Off,
+        MistakeMode::Immediate,
+        MistakeMode::Limited,
+        MistakeMode::OnDemand,
+    ];
+
+    /// Whether wrong placements add to the mistake counter. Modes that only
+    /// check on demand, or not at all, leave it alone.
+    pub fn counts_mistakes(self) -> bool {
+        matches!(self, MistakeMode::Immediate | MistakeMode::Limited)
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            MistakeMode::Off => "Off",
+            MistakeMode::Immediate => "Show immediately",
+            MistakeMode::Limited => "Limited mistakes",
+            MistakeMode::OnDemand => "Check on demand",
+        }
+    }
+}
+
+/// The mistake settings one game is played under, taken from the
+/// preference when it starts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MistakeRules {
+    pub mode: MistakeMode,
+    pub limit: u32,
+}
+
+impl MistakeRules {
+    /// Whether `mistakes` use up the limit, ending the game.
+    pub fn exhausted_by(self, mistakes: u32) -> bool {
+        self.mode == MistakeMode::Limited && mistakes >= self.limit
+    }
+}
+
+#[derive(PartialEq)]
+enum Hint {
+    /// A placed digit that doesn't match the solution; logic on top of it
+    /// would mislead.
+    Mistake(usize, usize),
+    /// Steps up to the next placement, see
+    /// [`LogicalSolver::steps_to_next_placement`]. Empty when logic is stuck.
+    Steps(Vec<Deduction>),
 }
 
 struct SolverState {
@@ -29,171 +178,1072 @@ struct SolverStep {
 
 impl Default for SudokuApp {
     fn default() -> Self {
+        let mut app = Self::blank();
+        app.new_game(Difficulty::Medium);
+        app
+    }
+}
+
+impl SudokuApp {
+    /// Resumes the saved game and settings if there are any, otherwise
+    /// starts a new game.
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let mut app = Self::blank();
+        let storage = cc.storage;
+        if let Some(settings) = storage.and_then(|storage| eframe::get_value(storage, SETTINGS_KEY)) {
+            app.apply_settings(settings);
+        }
+        if let Some(library) = storage.and_then(|storage| eframe::get_value(storage, LIBRARY_KEY)) {
+            app.library = library;
+        }
+        match storage.and_then(|storage| eframe::get_value(storage, GAME_KEY)) {
+            Some(saved) => {
+                app.restore(saved);
+                app.current_slot = storage.and_then(|storage| eframe::get_value(storage, CURRENT_SLOT_KEY));
+            }
+            None => app.new_game(Difficulty::Medium),
+        }
+        let stats_path = eframe::storage_dir(APP_NAME).map(|dir| dir.join(STATS_FILE));
+        if let Some(path) = stats_path {
+            match GameStats::load(&path) {
+                Ok(stats) => {
+                    app.stats = stats;
+                    app.stats_path = Some(path);
+                }
+                // Leave an unreadable log alone rather than overwrite it.
+                Err(error) => app.puzzle_notice = Some(format!("Couldn't read statistics: {error}")),
+            }
+        }
+        app
+    }
+
+    // An empty board with default settings and no game.
+    fn blank() -> Self {
+        let settings = Settings::default();
         Self {
-            grid: Generator::generate(Difficulty::Medium),
+            grid: Grid::new(),
             selected_cell: None,
             game_won: false,
             solving: false,
             solver_state: None,
             last_step_time: Instant::now(),
             input_mode: false,
+            notes_mode: false,
+            auto_candidates: settings.auto_candidates,
+            show_conflicts: settings.show_conflicts,
+            history: History::new(),
+            solution: None,
+            mistake_mode: settings.mistake_mode,
+            mistake_limit: settings.mistake_limit,
+            rules: settings.mistake_rules(),
+            lost: false,
+            mistakes: 0,
+            checked: false,
+            hint: None,
+            hints_used: 0,
+            clock: Stopwatch::new(),
+            paused: false,
+            solved_by_solver: false,
+            puzzle_notice: None,
+            solver_kind: settings.solver_kind,
+            last_solve_stats: None,
+            rating: None,
+            game: None,
+            code_input: String::new(),
+            generator_options: settings.generator_options,
+            library: Library::new(),
+            current_slot: None,
+            show_library: false,
+            slot_name: String::new(),
+            renaming: None,
+            stats: GameStats::new(),
+            stats_path: None,
+            recorded: false,
+            moves_made: false,
+            show_stats: false,
+            copy_pretty: false,
+            tab_pressed: false,
         }
     }
+
+    fn settings(&self) -> Settings {
+        Settings {
+            auto_candidates: self.auto_candidates,
+            show_conflicts: self.show_conflicts,
+            mistake_mode: self.mistake_mode,
+            mistake_limit: self.mistake_limit,
+            solver_kind: self.solver_kind,
+            generator_options: self.generator_options.clone(),
+        }
+    }
+
+    fn apply_settings(&mut self, settings: Settings) {
+        self.auto_candidates = settings.auto_candidates;
+        self.show_conflicts = settings.show_conflicts;
+        self.mistake_mode = settings.mistake_mode;
+        self.mistake_limit = settings.mistake_limit;
+        self.solver_kind = settings.solver_kind;
+        self.generator_options = settings.generator_options;
+    }
+
+    fn saved_game(&self) -> SavedGame {
+        SavedGame {
+            grid: self.grid.clone(),
+            solution: self.solution.clone(),
+            game: self.game.clone(),
+            input_mode: self.input_mode,
+            elapsed: self.clock.elapsed(),
+            history: self.history.clone(),
+            mistakes: self.mistakes,
+            hints_used: self.hints_used,
+            game_won: self.game_won,
+            solved_by_solver: self.solved_by_solver,
+            recorded: self.recorded,
+            moves_made: self.moves_made,
+            mistake_rules: Some(self.rules),
+            lost: self.lost,
+        }
+    }
+
+    fn restore(&mut self, saved: SavedGame) {
+        self.reset_play_state();
+        let settings = self.settings();
+        self.recorded = saved.counted(&settings);
+        self.moves_made = saved.played();
+        self.rules = saved.mistake_rules(&settings);
+        self.lost = saved.lost(&settings);
+        self.grid = saved.grid;
+        self.solution = saved.solution;
+        self.input_mode = saved.input_mode;
+        self.clock = Stopwatch::from_elapsed(saved.elapsed);
+        self.history = saved.history;
+        self.mistakes = saved.mistakes;
+        self.hints_used = saved.hints_used;
+        self.game_won = saved.game_won;
+        self.solved_by_solver = saved.solved_by_solver;
+        self.game = saved.game;
+        if let Some((difficulty, ref options, seed)) = self.game {
+            self.code_input = GameCode::new(difficulty, seed, options).to_string();
+        }
+        self.selected_cell = None;
+        self.last_solve_stats = None;
+        self.puzzle_notice = None;
+        self.rating = (!self.input_mode).then(|| {
+            let mut givens = self.grid.clone();
+            givens.clear_non_fixed();
+            Rating::of(&givens)
+        });
+    }
 }
 
 impl eframe::App for SudokuApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, SETTINGS_KEY, &self.settings());
+        // Mid-animation the board is half the solver's work; the last save
+        // from before the animation is the better one to resume.
+        if !self.solving {
+            self.sync_current_slot();
+            eframe::set_value(storage, GAME_KEY, &self.saved_game());
+        }
+        eframe::set_value(storage, LIBRARY_KEY, &self.library);
+        eframe::set_value(storage, CURRENT_SLOT_KEY, &self.current_slot);
+    }
+
+    fn auto_save_interval(&self) -> Duration {
+        Duration::from_secs(10)
+    }
+
+    // Tab belongs to the board: it selects the next empty cell, or leaves a
+    // text field, but never focuses a widget.
+    fn raw_input_hook(&mut self, ctx: &egui::Context, raw_input: &mut egui::RawInput) {
+        if take_tab_presses(raw_input) {
+            if ctx.wants_keyboard_input() {
+                ctx.memory_mut(|memory| memory.stop_text_input());
+            } else {
+                self.tab_pressed = true;
+            }
+        }
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         if self.solving{
             ctx.request_repaint();
         }
 
+        // Leaving the window pauses the game, so the clock only counts time
+        // actually spent on the puzzle.
+        if self.clock.is_running() && !ctx.input(|i| i.focused) {
+            self.paused = true;
+        }
+
+        // Ctrl+V anywhere but a text field pastes a puzzle.
+        if !self.solving && !ctx.wants_keyboard_input() {
+            let pasted = ctx.input(|i| {
+                i.events.iter().find_map(|event| match event {
+                    egui::Event::Paste(text) => Some(text.clone()),
+                    _ => None,
+                })
+            });
+            if let Some(text) = pasted {
+                self.paste_puzzle(&text);
+            }
+        }
+
+        if !self.solving && !self.game_over() && !self.paused {
+            if !self.input_mode {
+                self.handle_history_shortcuts(ctx);
+            }
+            self.handle_board_keys(ctx);
+        }
+        self.tab_pressed = false;
+
         egui::CentralPanel::default().show(ctx, |ui|{
-            ui.heading("Sudoku Game");
-            ui.add_space(10.0);
+            // The controls and board are taller than small windows, so keep
+            // everything reachable by scrolling.
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                ui.heading("Sudoku Game");
+                ui.add_space(10.0);
 
-            // Mode toggle button
-            ui.horizontal(|ui| {
-                ui.add_enabled_ui(!self.solving, |ui| {
-                    if self.input_mode {
-                        if ui.button("✓ Done - Lock Puzzle").clicked() {
-                            // Lock all non-zero cells as fixed
-                            for row in 0..9 {
-                                for col in 0..9 {
-                                    if self.grid.get(row, col) != 0 {
-                                        self.grid.set_fixed(row, col, true);
-                                    } else {
-                                        self.grid.set_fixed(row, col, false);
+                // Mode toggle button
+                ui.horizontal(|ui| {
+                    ui.add_enabled_ui(!self.solving, |ui| {
+                        if self.input_mode {
+                            if ui.button("✓ Done - Lock Puzzle").clicked() {
+                                self.lock_custom_puzzle();
+                            }
+                            ui.colored_label(egui::Color32::from_rgb(255, 100, 100), "INPUT MODE: Enter your puzzle");
+                        } else {
+                            if ui.button("📝 Input Custom Puzzle").clicked() {
+                                self.abandon_current_game();
+                                self.sync_current_slot();
+                                self.grid = Grid::new();
+                                self.input_mode = true;
+                                self.game = None;
+                                self.game_won = false;
+                                self.last_solve_stats = None;
+                                self.selected_cell = None;
+                                self.puzzle_notice = None;
+                                self.solution = None;
+                                self.current_slot = None;
+                                self.reset_play_state();
+                            }
+                            ui.toggle_value(&mut self.show_library, "📚 Library");
+                            ui.toggle_value(&mut self.show_stats, "📊 Statistics");
+                        }
+                        if ui.button("📋 Paste Puzzle").clicked() {
+                            match arboard::Clipboard::new().and_then(|mut clipboard| clipboard.get_text()) {
+                                Ok(text) => self.paste_puzzle(&text),
+                                Err(error) => self.puzzle_notice = Some(format!("Couldn't read the clipboard: {error}")),
+                            }
+                        }
+                        if let Some(notice) = &self.puzzle_notice {
+                            ui.colored_label(egui::Color32::from_rgb(255, 100, 100), notice);
+                        }
+                    });
+                });
+
+                ui.add_space(5.0);
+
+                ui.horizontal_wrapped(|ui| {
+                    ui.add_enabled_ui(!self.solving && !self.input_mode, |ui| {
+                        for difficulty in Difficulty::ALL {
+                            if ui.button(format!("New Game ({difficulty:?})")).clicked() {
+                                self.new_game(difficulty);
+                            }
+                        }
+                        egui::ComboBox::from_id_salt("symmetry")
+                            .selected_text(format!("Symmetry: {}", self.generator_options.symmetry.name()))
+                            .show_ui(ui, |ui| {
+                                for symmetry in Symmetry::ALL {
+                                    ui.selectable_value(&mut self.generator_options.symmetry, symmetry, symmetry.name());
+                                }
+                            });
+                        ui.checkbox(&mut self.generator_options.minimal, "Minimal");
+                    });
+                });
+
+                ui.horizontal(|ui| {
+                    ui.add_enabled_ui(!self.solving && !self.input_mode, |ui| {
+                        ui.label("Game code:");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.code_input)
+                                .hint_text("medium-123456")
+                                .desired_width(160.0),
+                        );
+                        if ui.button("Play Code").clicked() {
+                            match self.code_input.parse() {
+                                Ok(code) => self.start_game(code),
+                                Err(error) => self.puzzle_notice = Some(format!("Couldn't read the game code: {error}")),
+                            }
+                        }
+                    });
+                });
+
+                if !self.input_mode {
+                    ui.horizontal(|ui| {
+                        if let Some((difficulty, ref options, seed)) = self.game {
+                            let mut label = format!("{difficulty:?} game #{seed}");
+                            if options.symmetry != Symmetry::None {
+                                label += &format!(", {} symmetry", options.symmetry.name());
+                            }
+                            if options.minimal {
+                                label += ", minimal";
+                            }
+                            ui.label(label);
+                        }
+                        let clues = (0..81).filter(|&i| self.grid.is_fixed(i / 9, i % 9)).count();
+                        ui.label(format!("{clues} clues"));
+                        if let Some(rating) = self.rating {
+                            ui.label(format!("Rating: {rating}"));
+                        }
+                        if self.hints_used > 0 {
+                            ui.label(format!("{} hints used", self.hints_used));
+                        }
+                        ui.separator();
+                        ui.label(format!("⏱ {}", format_duration(self.clock.elapsed())));
+                        let can_pause = !self.game_won && !self.game_over() && !self.solving;
+                        let label = if self.paused { "▶ Resume" } else { "⏸ Pause" };
+                        if ui.add_enabled(can_pause, egui::Button::new(label)).clicked() {
+                            self.paused = !self.paused;
+                        }
+                    });
+                }
+
+                ui.add_space(5.0);
+
+                ui.horizontal_wrapped(|ui| {
+                    if !self.solving && !self.input_mode {
+                        // Everything here changes the hidden board, so none of it
+                        // works while paused.
+                        ui.add_enabled_ui(!self.paused, |ui| {
+                            if ui.button("Solve").clicked() {
+                                let mut solving_grid = self.grid.clone();
+
+                                for row in 0..9 {
+                                    for col in 0..9 {
+                                        solving_grid.set_fixed(row, col, false);
+                                    }
+                                }
+
+                                let mut stats = SolveStats::default();
+                                if self.solver_kind.backend().solve_with_stats(&mut solving_grid, &mut stats){
+                                    for row in 0..9{
+                                        for col in 0..9{
+                                            let value = solving_grid.get(row, col);
+                                            self.grid.set(row, col, value);
+                                        }
                                     }
+                                    self.game_won = true;
+                                    self.solved_by_solver = true;
+                                    self.history.clear();
+                                    self.hint = None;
                                 }
+                                self.last_solve_stats = Some((self.solver_kind, stats));
+                            }
+                            if ui.button("Solve (Animated)").clicked(){
+                                self.start_animated_solve();
+                            }
+                            if ui.add_enabled(!self.game_won && !self.game_over(), egui::Button::new("💡 Hint")).clicked() {
+                                self.show_hint();
                             }
-                            self.input_mode = false;
-                            self.game_won = false;
+
+                            if ui.button("Clear My Entries").clicked(){
+                                self.record_edit(|app| {
+                                    app.grid.clear_non_fixed();
+                                    if app.auto_candidates {
+                                        app.grid.fill_candidate_notes();
+                                    }
+                                });
+                                self.game_won = false;
+                                self.last_solve_stats = None;
+                            }
+
+                            ui.checkbox(&mut self.show_conflicts, "Show conflicts");
+
+                            if ui.button("Fill Candidates").clicked() {
+                                self.record_edit(|app| app.grid.fill_candidate_notes());
+                            }
+                            if ui.checkbox(&mut self.auto_candidates, "Auto candidates").changed() && self.auto_candidates {
+                                self.record_edit(|app| app.grid.fill_candidate_notes());
+                            }
+
+                            if ui.add_enabled(self.history.can_undo() && !self.game_over(), egui::Button::new("↶ Undo")).clicked() {
+                                self.undo();
+                            }
+                            if ui.add_enabled(self.history.can_redo() && !self.game_over(), egui::Button::new("↷ Redo")).clicked() {
+                                self.redo();
+                            }
+
+                            egui::ComboBox::from_id_salt("solver_kind")
+                                .selected_text(self.solver_kind.name())
+                                .show_ui(ui, |ui| {
+                                    for kind in SolverKind::ALL {
+                                        ui.selectable_value(&mut self.solver_kind, kind, kind.name());
+                                    }
+                                });
+                        });
+                    } else if self.input_mode {
+                        if ui.button("Clear All").clicked() {
+                            self.grid = Grid::new();
                             self.selected_cell = None;
                         }
-                        ui.colored_label(egui::Color32::from_rgb(255, 100, 100), "INPUT MODE: Enter your puzzle");
                     } else {
-                        if ui.button("📝 Input Custom Puzzle").clicked() {
-                            self.grid = Grid::new();
-                            self.input_mode = true;
-                            self.game_won = false;
-                            self.selected_cell = None;
+                        if ui.button("Stop Animation").clicked() {
+                            self.solving = false;
+                            self.solver_state = None;
                         }
+                        ui.label("Solving...");
                     }
                 });
-            });
 
-            ui.add_space(5.0);
+                if !self.solving && !self.input_mode && !self.paused {
+                    self.draw_hint(ui);
+                }
 
-            ui.horizontal(|ui| {
-                ui.add_enabled_ui(!self.solving && !self.input_mode, |ui| {
-                    if ui.button("New Game (Easy)").clicked() {
-                        self.grid = Generator::generate(Difficulty::Easy);
-                        self.selected_cell = None;
-                        self.game_won = false;
-                    }
-                    if ui.button("New Game (Medium)").clicked() {
-                        self.grid = Generator::generate(Difficulty::Medium);
-                        self.selected_cell = None;
-                        self.game_won = false;
-                    }
-                    if ui.button("New Game (Hard)").clicked(){
-                        self.grid = Generator::generate(Difficulty::Hard);
-                        self.selected_cell = None;
-                        self.game_won = false;
+                if !self.input_mode {
+                    ui.horizontal(|ui| {
+                        // The rules can only change before the first move or
+                        // once the game is over; then they apply to the next one.
+                        let started = self.moves_made && !self.game_won && !self.lost;
+                        ui.add_enabled_ui(!started, |ui| {
+                            egui::ComboBox::from_id_salt("mistake_mode")
+                                .selected_text(format!("Mistakes: {}", self.mistake_mode.name()))
+                                .show_ui(ui, |ui| {
+                                    for mode in MistakeMode::ALL {
+                                        ui.selectable_value(&mut self.mistake_mode, mode, mode.name());
+                                    }
+                                });
+                            if self.mistake_mode == MistakeMode::Limited {
+                                ui.add(egui::DragValue::new(&mut self.mistake_limit).range(1..=20).prefix("limit "));
+                            }
+                        });
+                        if !self.moves_made && !self.game_won {
+                            self.rules = self.settings().mistake_rules();
+                        } else if self.rules != self.settings().mistake_rules() {
+                            ui.label("(from the next game)");
+                        }
+                        match self.rules.mode {
+                            MistakeMode::Off => {}
+                            MistakeMode::Immediate => {
+                                ui.label(format!("{} mistakes", self.mistakes));
+                            }
+                            MistakeMode::Limited => {
+                                ui.label(format!("{} of {} mistakes", self.mistakes, self.rules.limit));
+                            }
+                            MistakeMode::OnDemand => {
+                                if ui.add_enabled(!self.solving, egui::Button::new("Check")).clicked() {
+                                    self.checked = true;
+                                }
+                            }
+                        }
+                        if self.rules.mode != MistakeMode::Off && self.solution.is_none() {
+                            ui.label("(no unique solution to check against)");
+                        }
+                    });
+                }
+
+                if !self.input_mode {
+                    ui.horizontal(|ui| {
+                        let export = |grid: &Grid| if self.copy_pretty { grid.to_pretty() } else { grid.to_line() };
+                        if ui.button("Copy Puzzle").clicked() {
+                            let mut givens = self.grid.clone();
+                            givens.clear_non_fixed();
+                            ui.ctx().copy_text(export(&givens));
+                        }
+                        if ui.button("Copy Current State").clicked() {
+                            ui.ctx().copy_text(export(&self.grid));
+                        }
+                        if let Some(solution) = &self.solution
+                            && ui.button("Copy Solution").clicked() {
+                            ui.ctx().copy_text(export(solution));
+                        }
+                        ui.checkbox(&mut self.copy_pretty, "As grid");
+                    });
+                }
+
+                if let Some((kind, stats)) = self.last_solve_stats {
+                    ui.label(format!(
+                        "{}: {} nodes, {} backtracks, max depth {}",
+                        kind.name(),
+                        stats.nodes,
+                        stats.backtracks,
+                        stats.max_depth,
+                    ));
+                }
+
+                if self.solving {
+                    ui.horizontal(|ui|{
+                        ui.label("Speed:");
+                        if let Some(ref mut state) = self.solver_state {
+                            ui.add(egui::Slider::new(&mut state.speed_ms, 1..=500)
+                                .text("ms"));
+                        }
+                    });
+                }
+
+                ui.add_space(20.0);
+
+                if self.paused {
+                    self.draw_paused(ui);
+                } else {
+                    self.draw_grid(ui);
+                }
+
+                if self.solving {
+                    let now = Instant::now();
+                    if let Some(ref state) = self.solver_state
+                        && now.duration_since(self.last_step_time) >= Duration::from_millis(state.speed_ms){
+                        self.last_step_time = now;
+                        self.step_solve();
                     }
-                });
+                }
+
+                ui.add_space(20.0);
+                if self.game_over() {
+                    ui.colored_label(
+                        egui::Color32::RED,
+                        format!("Game over: {} mistakes. Start a new game to try again.", self.mistakes),
+                    );
+                } else if !self.solving && !self.paused {
+                    self.draw_number_buttons(ui);
+                }
+            
+                if !self.input_mode && (self.game_won || self.grid.is_complete()){
+                    self.game_won = true;
+                    ui.add_space(10.0);
+                    ui.colored_label(egui::Color32::GREEN, self.completion_summary());
+                }
             });
+        });
 
-            ui.add_space(5.0);
+        if self.show_library {
+            self.draw_library(ctx);
+        }
+        if self.show_stats {
+            self.draw_stats(ctx);
+        }
+
+        self.record_finished_game();
+
+        self.update_clock(ctx);
+    }
+}
+
+impl SudokuApp {
+    fn new_game(&mut self, difficulty: Difficulty) {
+        let seed = rand::thread_rng().gen_range(0..1_000_000);
+        self.start_game(GameCode::new(difficulty, seed, &self.generator_options));
+    }
+
+    fn start_game(&mut self, code: GameCode) {
+        self.abandon_current_game();
+        self.sync_current_slot();
+        self.current_slot = None;
+        let puzzle = code.generate();
+        self.grid = puzzle.grid;
+        self.solution = Some(puzzle.solution);
+        self.game = Some((code.difficulty, code.options(), code.seed));
+        self.code_input = code.to_string();
+        self.rating = Some(Rating::of(&self.grid));
+        self.selected_cell = None;
+        self.game_won = false;
+        self.last_solve_stats = None;
+        self.puzzle_notice = None;
+        self.reset_play_state();
+        if self.auto_candidates {
+            self.grid.fill_candidate_notes();
+        }
+    }
 
+    // Forgets everything about how the previous puzzle was played.
+    fn reset_play_state(&mut self) {
+        self.history.clear();
+        self.mistakes = 0;
+        self.checked = false;
+        self.hint = None;
+        self.hints_used = 0;
+        self.clock = Stopwatch::new();
+        self.paused = false;
+        self.solved_by_solver = false;
+        self.recorded = false;
+        self.moves_made = false;
+        self.rules = self.settings().mistake_rules();
+        self.lost = false;
+    }
+
+    // Adds the current game to the statistics the first time it ends.
+    fn record_finished_game(&mut self) {
+        let outcome = if self.game_over() {
+            Outcome::OutOfMistakes
+        } else if self.game_won && self.solved_by_solver {
+            Outcome::SolvedBySolver
+        } else if self.game_won {
+            Outcome::Solved
+        } else {
+            return;
+        };
+        self.record_game(outcome);
+    }
+
+    // Called before the current game is replaced. Leaving an unfinished game
+    // counts against the player, unless it is kept in a slot to come back to
+    // or nothing was played yet.
+    fn abandon_current_game(&mut self) {
+        if self.current_slot.is_none() && self.saved_game().abandoned_if_replaced(&self.settings()) {
+            self.record_game(Outcome::Abandoned);
+        }
+    }
+
+    fn record_game(&mut self, outcome: Outcome) {
+        if self.recorded || self.input_mode {
+            return;
+        }
+        self.stats.record(FinishedGame {
+            difficulty: self.game.as_ref().map(|&(difficulty, _, _)| difficulty),
+            seed: self.game.as_ref().map(|&(_, _, seed)| seed),
+            time: self.clock.elapsed(),
+            mistakes: self.mistakes,
+            hints: self.hints_used,
+            outcome,
+            finished_at: SystemTime::now(),
+        });
+        self.recorded = true;
+        if let Some(path) = &self.stats_path
+            && let Err(error) = self.stats.save(path) {
+            self.puzzle_notice = Some(format!("Couldn't save statistics: {error}"));
+        }
+    }
+
+    fn draw_stats(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        egui::Window::new("Statistics").open(&mut open).show(ctx, |ui| {
+            egui::Grid::new("stats_by_difficulty").striped(true).show(ui, |ui| {
+                for heading in ["", "Played", "Won", "Best", "Average"] {
+                    ui.strong(heading);
+                }
+                ui.end_row();
+
+                let rows = Difficulty::ALL.map(|difficulty| (format!("{difficulty:?}"), Some(difficulty)));
+                for (label, difficulty) in rows.into_iter().chain([("Custom".to_owned(), None)]) {
+                    let summary = self.stats.summary(difficulty);
+                    let time = |time: Option<Duration>| time.map_or("-".to_owned(), format_duration);
+                    ui.label(label);
+                    ui.label(summary.played.to_string());
+                    ui.label(summary.won.to_string());
+                    ui.label(time(summary.best));
+                    ui.label(time(summary.average));
+                    ui.end_row();
+                }
+            });
+            ui.label(format!(
+                "Win streak: {} (best {})",
+                self.stats.current_streak(),
+                self.stats.best_streak()
+            ));
+
+            ui.separator();
+            ui.label("Completion times");
+            let longest = self.stats.games().iter().filter(|game| game.won()).map(|game| game.time).max();
+            let Some(longest) = longest else {
+                ui.label("No puzzles solved yet.");
+                return;
+            };
+            // Minute-wide bars, or five minutes once games run long. Past
+            // the last bar, times pile into it so the chart keeps its width.
+            const MAX_BARS: usize = 24;
+            let width = if longest <= Duration::from_secs(20 * 60) { 1 } else { 5 };
+            let buckets = self.stats.time_histogram(Duration::from_secs(width * 60), MAX_BARS);
+            let tallest = buckets.iter().copied().max().unwrap_or(1).max(1);
+
+            let bar_width = 18.0;
+            let height = 100.0;
+            let (rect, _) = ui.allocate_exact_size(
+                egui::Vec2::new(bar_width * buckets.len() as f32, height + 16.0),
+                egui::Sense::hover(),
+            );
+            let painter = ui.painter();
+            for (i, &count) in buckets.iter().enumerate() {
+                let left = rect.min.x + i as f32 * bar_width;
+                let bar_height = height * count as f32 / tallest as f32;
+                painter.rect_filled(
+                    egui::Rect::from_min_max(
+                        egui::pos2(left + 2.0, rect.min.y + height - bar_height),
+                        egui::pos2(left + bar_width - 2.0, rect.min.y + height),
+                    ),
+                    0.0,
+                    egui::Color32::from_rgb(120, 160, 220),
+                );
+                let last = i + 1 == MAX_BARS;
+                if i % 5 == 0 || last {
+                    painter.text(
+                        egui::pos2(left, rect.min.y + height + 2.0),
+                        egui::Align2::LEFT_TOP,
+                        format!("{}m{}", i as u64 * width, if last { "+" } else { "" }),
+                        egui::FontId::proportional(11.0),
+                        egui::Color32::DARK_GRAY,
+                    );
+                }
+            }
+        });
+        self.show_stats &= open;
+    }
+
+    // Turns what's on the board into a custom puzzle to play, with the
+    // filled cells as givens.
+    fn lock_custom_puzzle(&mut self) {
+        for row in 0..9 {
+            for col in 0..9 {
+                let given = self.grid.get(row, col) != 0;
+                self.grid.set_fixed(row, col, given);
+            }
+        }
+        self.input_mode = false;
+        self.game_won = false;
+        self.last_solve_stats = None;
+        self.selected_cell = None;
+        let mut solution = self.grid.clone();
+        self.puzzle_notice = if !Dlx.solve(&mut solution) {
+            Some("This puzzle has no solution".to_owned())
+        } else if !solver::is_unique(&self.grid) {
+            Some("This puzzle has more than one solution".to_owned())
+        } else {
+            None
+        };
+        self.rating = Some(Rating::of(&self.grid));
+        self.solution = self.puzzle_notice.is_none().then_some(solution);
+        self.reset_play_state();
+        if self.auto_candidates {
+            self.grid.fill_candidate_notes();
+        }
+    }
+
+    // Starts a custom game from text in the one-line or grid-art format.
+    fn paste_puzzle(&mut self, text: &str) {
+        match text.parse::<Grid>() {
+            Ok(grid) => {
+                self.abandon_current_game();
+                self.sync_current_slot();
+                self.current_slot = None;
+                self.game = None;
+                self.grid = grid;
+                self.lock_custom_puzzle();
+            }
+            Err(error) => self.puzzle_notice = Some(format!("Couldn't read the pasted puzzle: {error}")),
+        }
+    }
+
+    // Writes the game in progress back to its slot, so slots follow play.
+    fn sync_current_slot(&mut self) {
+        if let Some(name) = &self.current_slot
+            && !self.input_mode {
+            self.library.update(name, self.saved_game(), SystemTime::now());
+        }
+    }
+
+    fn draw_library(&mut self, ctx: &egui::Context) {
+        let mut open = true;
+        egui::Window::new("Library").open(&mut open).show(ctx, |ui| {
             ui.horizontal(|ui| {
-                if !self.solving && !self.input_mode {
-                    if ui.button("Solve").clicked() {
-                        let mut solving_grid = self.grid.clone();
+                ui.add(egui::TextEdit::singleline(&mut self.slot_name).hint_text("Slot name").desired_width(140.0));
+                let can_save = !self.input_mode && !self.solving && !self.slot_name.trim().is_empty();
+                if ui.add_enabled(can_save, egui::Button::new("Save Current Game")).clicked() {
+                    // Never overwrite another slot; a taken name gets a number.
+                    let name = self.library.unused_name(self.slot_name.trim());
+                    self.library.save(&name, self.saved_game(), SystemTime::now());
+                    self.current_slot = Some(name);
+                    self.slot_name.clear();
+                }
+            });
+            if let Some(name) = &self.current_slot {
+                ui.label(format!("Playing \"{name}\""));
+            }
+            ui.separator();
 
-                        for row in 0..9 {
-                            for col in 0..9 {
-                                solving_grid.set_fixed(row, col, false);
-                            }
-                        }
+            if self.library.slots().is_empty() {
+                ui.label("No saved games yet.");
+                return;
+            }
 
-                        if Solver::solve(&mut solving_grid){
-                            for row in 0..9{
-                                for col in 0..9{
-                                    let value = solving_grid.get(row, col);
-                                    self.grid.set(row, col, value);
-                                }
+            // Actions are collected and run after the loop, which borrows
+            // the library.
+            let mut load = None;
+            let mut duplicate = None;
+            let mut delete = None;
+            let mut rename = None;
+            egui::Grid::new("library_slots").striped(true).show(ui, |ui| {
+                for heading in ["Name", "Difficulty", "Progress", "Time", "Last played", ""] {
+                    ui.strong(heading);
+                }
+                ui.end_row();
+
+                for slot in self.library.slots() {
+                    match &mut self.renaming {
+                        Some((old, new_name)) if *old == slot.name => {
+                            let edit = ui.add(egui::TextEdit::singleline(new_name).desired_width(120.0));
+                            if edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                                rename = Some((old.clone(), new_name.clone()));
                             }
-                            self.game_won = true;
+                        }
+                        _ => {
+                            ui.label(&slot.name);
                         }
                     }
-                    if ui.button("Solve (Animated)").clicked(){
-                        self.start_animated_solve();
-                    }
-
-                    if ui.button("Clear My Entries").clicked(){
-                        self.grid.clear_non_fixed();
-                        self.game_won = false;
-                    }
-                } else if self.input_mode {
-                    if ui.button("Clear All").clicked() {
-                        self.grid = Grid::new();
-                        self.selected_cell = None;
-                    }
-                } else {
-                    if ui.button("Stop Animation").clicked() {
-                        self.solving = false;
-                        self.solver_state = None;
-                    }
-                    ui.label("Solving...");
+                    ui.label(match slot.game.game {
+                        Some((difficulty, _, seed)) => format!("{difficulty:?} #{seed}"),
+                        None => "Custom".to_owned(),
+                    });
+                    let progress = if slot.game.game_won {
+                        "Solved".to_owned()
+                    } else {
+                        format!("{:.0}%", slot.game.progress() * 100.0)
+                    };
+                    ui.label(progress);
+                    ui.label(format_duration(slot.game.elapsed));
+                    ui.label(format_date(slot.last_played));
+                    ui.horizontal(|ui| {
+                        if ui.add_enabled(!self.solving, egui::Button::new("Load")).clicked() {
+                            load = Some(slot.name.clone());
+                        }
+                        if ui.button("Duplicate").clicked() {
+                            duplicate = Some(slot.name.clone());
+                        }
+                        if ui.button("Rename").clicked() {
+                            self.renaming = Some((slot.name.clone(), slot.name.clone()));
+                        }
+                        if ui.button("Delete").clicked() {
+                            delete = Some(slot.name.clone());
+                        }
+                    });
+                    ui.end_row();
                 }
             });
 
-            if self.solving {
-                ui.horizontal(|ui|{
-                    ui.label("Speed:");
-                    if let Some(ref mut state) = self.solver_state {
-                        ui.add(egui::Slider::new(&mut state.speed_ms, 1..=500)
-                            .text("ms"));
-                    }
-                });
+            if let Some(name) = load {
+                self.abandon_current_game();
+                self.sync_current_slot();
+                if let Some(game) = self.library.get(&name).map(|slot| slot.game.clone()) {
+                    self.restore(game);
+                    self.current_slot = Some(name);
+                }
+            }
+            if let Some(name) = duplicate {
+                self.library.duplicate(&name);
             }
+            if let Some((old, new_name)) = rename
+                && self.library.rename(&old, &new_name) {
+                if self.current_slot.as_ref() == Some(&old) {
+                    self.current_slot = Some(new_name.trim().to_owned());
+                }
+                self.renaming = None;
+            }
+            if let Some(name) = delete {
+                self.library.delete(&name);
+                if self.current_slot.as_ref() == Some(&name) {
+                    self.current_slot = None;
+                }
+            }
+        });
+        self.show_library &= open;
+    }
 
-            ui.add_space(20.0);
+    // The clock runs while the player can work on an unfinished game.
+    fn update_clock(&mut self, ctx: &egui::Context) {
+        // A finished game has nothing left to hide, and Resume is disabled
+        // once it ends.
+        if self.game_won || self.game_over() {
+            self.paused = false;
+        }
+        let playing = !self.input_mode
+            && !self.solving
+            && !self.paused
+            && !self.game_won
+            && !self.game_over();
+        if playing {
+            self.clock.start();
+            ctx.request_repaint_after(Duration::from_secs(1));
+        } else {
+            self.clock.pause();
+        }
+    }
 
-            self.draw_grid(ui);
+    fn completion_summary(&self) -> String {
+        let time = format_duration(self.clock.elapsed());
+        if self.solved_by_solver {
+            return format!("Solved by the solver after {time}.");
+        }
+        let plural = |count: u32, noun: &str| {
+            format!("{count} {noun}{}", if count == 1 { "" } else { "s" })
+        };
+        format!(
+            "🎉 Puzzle solved in {time} with {} and {}! 🎉",
+            plural(self.mistakes, "mistake"),
+            plural(self.hints_used, "hint"),
+        )
+    }
 
-            if self.solving {
-                let now = Instant::now();
-                if let Some(ref state) = self.solver_state{
-                    if now.duration_since(self.last_step_time) >= Duration::from_millis(state.speed_ms){
-                        self.last_step_time = now;
-                        self.step_solve();
+    // Stands in for the board while paused, so the puzzle can't be studied
+    // off the clock.
+    fn draw_paused(&self, ui: &mut egui::Ui) {
+        let size = egui::Vec2::splat(CELL_SIZE * 9.0);
+        let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+        ui.painter().rect_filled(rect, 0.0, egui::Color32::from_rgb(240, 240, 240));
+        ui.painter().text(
+            rect.center(),
+            egui::Align2::CENTER_CENTER,
+            "Paused",
+            egui::FontId::proportional(30.0),
+            egui::Color32::DARK_GRAY,
+        );
+    }
+
+    fn show_hint(&mut self) {
+        let mistake = self.solution.as_ref().and_then(|solution| {
+            let mistakes = self.grid.mistakes(solution);
+            (0..81).map(|i| (i / 9, i % 9)).find(|&(row, col)| mistakes[row][col])
+        });
+        let hint = match mistake {
+            Some((row, col)) => Hint::Mistake(row, col),
+            None => Hint::Steps(LogicalSolver::new(&self.grid).steps_to_next_placement()),
+        };
+        // Only a hint that shows something new counts: asking again for the
+        // one on screen, or when logic is stuck, is free.
+        let reveals = match &hint {
+            Hint::Mistake(..) => true,
+            Hint::Steps(steps) => !steps.is_empty(),
+        };
+        if reveals && self.hint.as_ref() != Some(&hint) {
+            self.hints_used += 1;
+        }
+        self.hint = Some(hint);
+    }
+
+    // Makes the hinted change as one undoable move: fixes the mistake, or
+    // removes the eliminated notes and places the digit.
+    fn apply_hint(&mut self) {
+        let Some(hint) = self.hint.take() else {
+            return;
+        };
+        self.record_edit(|app| match hint {
+            Hint::Mistake(row, col) => app.enter_value(row, col, 0),
+            Hint::Steps(steps) => {
+                for step in &steps {
+                    for &(row, col, digit) in &step.eliminations {
+                        let mut notes = app.grid.notes(row, col);
+                        notes.remove(digit);
+                        app.grid.set_notes(row, col, notes);
+                    }
+                    if let Some((row, col, digit)) = step.placement {
+                        app.enter_value(row, col, digit);
                     }
                 }
             }
+        });
+    }
 
-            ui.add_space(20.0);
-            if !self.solving {
-                self.draw_number_buttons(ui);
+    fn draw_hint(&mut self, ui: &mut egui::Ui) {
+        let Some(hint) = &self.hint else {
+            return;
+        };
+        let can_apply = match hint {
+            Hint::Mistake(row, col) => {
+                ui.label(format!("r{}c{} is wrong.", row + 1, col + 1));
+                true
             }
-            
-            if !self.input_mode && (self.game_won || self.grid.is_complete()){
-                ui.add_space(10.0);
-                ui.colored_label(egui::Color32::GREEN, "🎉 Congratulations! Puzzle solved! 🎉");
-                self.game_won = true;
+            Hint::Steps(steps) => {
+                for step in steps {
+                    ui.label(step.explain());
+                }
+                let placement = steps.last().and_then(|step| step.placement);
+                if placement.is_none() {
+                    ui.label("No further logical step: this position needs guessing, or an entry is wrong.");
+                }
+                placement.is_some()
+            }
+        };
+        ui.horizontal(|ui| {
+            if ui.add_enabled(can_apply, egui::Button::new("Apply Hint")).clicked() {
+                self.apply_hint();
+            }
+            if ui.button("Dismiss").clicked() {
+                self.hint = None;
             }
         });
     }
-}
 
-impl SudokuApp {
+    fn game_over(&self) -> bool {
+        self.lost
+    }
+
+    // Wrong digits to show on the board under the current mistake mode.
+    fn visible_mistakes(&self) -> [[bool; 9]; 9] {
+        let show = match self.rules.mode {
+            MistakeMode::Off => false,
+            MistakeMode::Immediate | MistakeMode::Limited => true,
+            MistakeMode::OnDemand => self.checked,
+        };
+        match &self.solution {
+            Some(solution) if show && !self.input_mode => self.grid.mistakes(solution),
+            _ => [[false; 9]; 9],
+        }
+    }
+
+    // Runs a player edit and records everything it changed as one move.
+    fn record_edit(&mut self, edit: impl FnOnce(&mut Self)) -> Move {
+        let before = self.grid.clone();
+        edit(self);
+        let change = Move::between(&before, &self.grid);
+        self.moves_made |= !change.is_empty();
+        self.history.record(change.clone());
+        self.checked = false;
+        self.hint = None;
+        change
+    }
+
+    fn undo(&mut self) {
+        if self.history.undo(&mut self.grid) {
+            self.game_won = false;
+            self.checked = false;
+            self.hint = None;
+        }
+    }
+
+    fn redo(&mut self) {
+        if self.history.redo(&mut self.grid) {
+            self.checked = false;
+            self.hint = None;
+        }
+    }
+
+    fn handle_history_shortcuts(&mut self, ctx: &egui::Context) {
+        let redo_shift = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::Z);
+        let undo = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z);
+        let redo = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Y);
+
+        // Text fields such as the seed or slot name have their own undo.
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        // Shortcuts match ignoring extra Shift, so Ctrl+Shift+Z goes first.
+        if ctx.input_mut(|i| i.consume_shortcut(&redo_shift)) {
+            self.redo();
+        } else if ctx.input_mut(|i| i.consume_shortcut(&undo)) {
+            self.undo();
+        } else if ctx.input_mut(|i| i.consume_shortcut(&redo)) {
+            self.redo();
+        }
+    }
+
     fn start_animated_solve(&mut self){
+        // The animation overwrites the player's entries, so their moves can't
+        // be replayed on top of it.
+        self.history.clear();
+        self.moves_made = true;
+        self.hint = None;
         let mut solving_grid = self.grid.clone();
         let mut original_fixed = [[false; 9]; 9];
-        for row in 0..9{
-            for col in 0..9 {
-                original_fixed[row][col] = solving_grid.is_fixed(row, col);
+        for (row, fixed_row) in original_fixed.iter_mut().enumerate() {
+            for (col, fixed) in fixed_row.iter_mut().enumerate() {
+                *fixed = solving_grid.is_fixed(row, col);
             }
         }
 
@@ -221,16 +1271,16 @@ impl SudokuApp {
                 self.grid = state.current_grid.clone();
                 self.solving = false;
                 self.game_won = true;
+                self.solved_by_solver = true;
                 return;
             }
 
             let (row, col) = empty_pos.unwrap();
             let mut found = false;
             for num in 1..=9 {
-                if let Some(last_step) = state.stack.last() {
-                    if last_step.row == row && last_step.col == col && last_step.tried_numbers.contains(&num){
-                        continue;
-                    }
+                if let Some(last_step) = state.stack.last()
+                    && last_step.row == row && last_step.col == col && last_step.tried_numbers.contains(&num){
+                    continue;
                 }
 
                 if Solver::is_safe_static(&state.current_grid, row, col, num){
@@ -251,8 +1301,7 @@ impl SudokuApp {
 
 
     fn draw_grid(&mut self, ui: &mut egui::Ui){
-        let cell_size = 50.0;
-        let grid_size = cell_size * 9.0;
+        let grid_size = CELL_SIZE * 9.0;
 
         let (response, painter) = ui.allocate_painter(
             egui::Vec2::new(grid_size, grid_size), 
@@ -261,36 +1310,74 @@ impl SudokuApp {
 
         let origin = response.rect.min;
 
-        if !self.solving && response.clicked() {  
-            if let Some(pos) = response.interact_pointer_pos() {
-                let relative = pos - origin;
-                let col = (relative.x / cell_size) as usize;
-                let row = (relative.y / cell_size) as usize;
-                if row < 9 && col < 9 {
-                    // In input mode, allow selecting any cell
-                    // In play mode, only allow selecting non-fixed cells
-                    if self.input_mode || !self.grid.is_fixed(row, col) {
-                        self.selected_cell = Some((row, col));
+        if !self.solving && response.clicked()
+            && let Some(pos) = response.interact_pointer_pos() {
+            let relative = pos - origin;
+            let col = (relative.x / CELL_SIZE) as usize;
+            let row = (relative.y / CELL_SIZE) as usize;
+            if row < 9 && col < 9 {
+                // Givens can be selected too, to highlight their digit;
+                // entering values there does nothing outside input mode.
+                self.selected_cell = Some((row, col));
+            }
+        }
+
+        let mut flags = if self.show_conflicts && !self.solving {
+            self.grid.conflicts()
+        } else {
+            [[false; 9]; 9]
+        };
+        if !self.solving {
+            for (row_flags, mistakes) in flags.iter_mut().zip(self.visible_mistakes()) {
+                for (flag, mistake) in row_flags.iter_mut().zip(mistakes) {
+                    *flag |= mistake;
+                }
+            }
+        }
+
+        // Highlighting follows the selection only while the player is in control.
+        let selected = self.selected_cell.filter(|_| !self.solving);
+        let selected_value = selected.map_or(0, |(row, col)| self.grid.get(row, col));
+        let is_peer = |row: usize, col: usize| {
+            selected.is_some_and(|(sel_row, sel_col)| {
+                row == sel_row
+                    || col == sel_col
+                    || Grid::box_index(row, col) == Grid::box_index(sel_row, sel_col)
+            })
+        };
+
+        // What the current hint points at: the pattern cells, the units
+        // they're in, and any candidates it removes.
+        let mut hint_cells = [[false; 9]; 9];
+        let mut hint_area = [[false; 9]; 9];
+        let mut hint_eliminations = Vec::new();
+        match &self.hint {
+            Some(Hint::Mistake(row, col)) => hint_cells[*row][*col] = true,
+            Some(Hint::Steps(steps)) => {
+                for step in steps {
+                    for &(row, col) in &step.cells {
+                        hint_cells[row][col] = true;
+                    }
+                    for (row, col) in step.units.iter().flat_map(|&unit| Grid::unit_cells(unit)) {
+                        hint_area[row][col] = true;
                     }
+                    hint_eliminations.extend_from_slice(&step.eliminations);
                 }
             }
+            None => {}
         }
 
         let solving_cell = if let Some(ref state) = self.solver_state {
-            if let Some(last_step) = state.stack.last() {
-                Some((last_step.row, last_step.col))
-            } else {
-                None
-            }
+            state.stack.last().map(|last_step| (last_step.row, last_step.col))
         } else {
             None
         };
 
-        for row in 0..9 {
-            for col in 0..9 {
+        for (row, flag_row) in flags.iter().enumerate() {
+            for (col, &flagged) in flag_row.iter().enumerate() {
                 let rect = egui::Rect::from_min_size(
-                    origin + egui::Vec2::new(col as f32 * cell_size, row as f32 * cell_size),
-                    egui::Vec2::splat(cell_size),
+                    origin + egui::Vec2::new(col as f32 * CELL_SIZE, row as f32 * CELL_SIZE),
+                    egui::Vec2::splat(CELL_SIZE),
                 );
 
                 let is_originally_fixed = if let Some(ref state) = self.solver_state {
@@ -303,6 +1390,16 @@ impl SudokuApp {
                     egui::Color32::from_rgb(255, 200, 200) 
                 } else if Some((row, col)) == self.selected_cell {
                     egui::Color32::from_rgb(200, 220, 255)
+                } else if flagged {
+                    egui::Color32::from_rgb(255, 225, 225)
+                } else if hint_cells[row][col] {
+                    egui::Color32::from_rgb(255, 235, 160)
+                } else if hint_area[row][col] {
+                    egui::Color32::from_rgb(255, 248, 220)
+                } else if selected_value != 0 && self.grid.get(row, col) == selected_value {
+                    egui::Color32::from_rgb(215, 228, 250)
+                } else if is_peer(row, col) {
+                    egui::Color32::from_rgb(232, 239, 250)
                 } else if self.grid.is_fixed(row, col){
                     egui::Color32::from_rgb(240, 240, 240)
                 } else {
@@ -312,7 +1409,9 @@ impl SudokuApp {
 
                 let value = self.grid.get(row, col);
                 if value != 0 {
-                    let text_color = if is_originally_fixed{
+                    let text_color = if flagged {
+                        egui::Color32::RED
+                    } else if is_originally_fixed{
                         egui::Color32::BLACK
                     } else {
                         egui::Color32::BLUE
@@ -323,6 +1422,27 @@ impl SudokuApp {
                     egui::FontId::proportional(30.0), 
                     text_color,
                     );
+                } else {
+                    // Notes sit in a 3x3 mini-grid, digit 1 top left.
+                    let note_size = CELL_SIZE / 3.0;
+                    for digit in self.grid.notes(row, col).iter() {
+                        let index = (digit - 1) as f32;
+                        let center = rect.min + egui::Vec2::new(
+                            (index % 3.0 + 0.5) * note_size,
+                            ((index / 3.0).floor() + 0.5) * note_size,
+                        );
+                        let note_color = if hint_eliminations.contains(&(row, col, digit)) {
+                            egui::Color32::RED
+                        } else {
+                            egui::Color32::DARK_GRAY
+                        };
+                        painter.text(center,
+                        egui::Align2::CENTER_CENTER,
+                        digit.to_string(),
+                        egui::FontId::proportional(12.0),
+                        note_color,
+                        );
+                    }
                 }
 
                 painter.rect_stroke(rect, 0.0, egui::Stroke::new(1.0, egui::Color32::GRAY));
@@ -330,7 +1450,7 @@ impl SudokuApp {
         }
 
         for i in 0..=3{
-            let offset = i as f32 * cell_size * 3.0;
+            let offset = i as f32 * CELL_SIZE * 3.0;
             painter.line_segment(
                 [origin + egui::Vec2::new(0.0, offset),
                 origin + egui::Vec2::new(grid_size, offset)], 
@@ -372,35 +1492,134 @@ impl SudokuApp {
         }
     }
 
+    fn enter_value(&mut self, row: usize, col: usize, value: u8) {
+        if self.input_mode {
+            // In input mode, directly set the value
+            self.grid.set(row, col, value);
+            return;
+        }
+
+        let old = self.grid.get(row, col);
+        self.grid.set_user(row, col, value);
+        if self.auto_candidates && old != 0 && old != value {
+            self.grid.restore_candidate_notes(row, col, old);
+        }
+    }
+
+    // A digit from the number buttons or the keyboard: a note in notes mode,
+    // a placement otherwise.
+    fn press_digit(&mut self, row: usize, col: usize, num: u8) {
+        if self.input_mode {
+            self.enter_value(row, col, num);
+        } else if self.notes_mode {
+            self.record_edit(|app| app.grid.toggle_note(row, col, num));
+        } else {
+            let change = self.record_edit(|app| app.enter_value(row, col, num));
+            // Pressing the same digit again changes nothing and isn't a
+            // second mistake.
+            if self.rules.mode.counts_mistakes()
+                && let Some(solution) = &self.solution
+                && change.placed(row, col).is_some_and(|placed| solution.get(row, col) != placed) {
+                self.mistakes += 1;
+                self.lost |= self.rules.exhausted_by(self.mistakes);
+            }
+        }
+    }
+
+    // Clears the cell's value, or its notes if it has no value.
+    fn clear_cell(&mut self, row: usize, col: usize) {
+        if self.input_mode {
+            self.enter_value(row, col, 0);
+        } else if self.grid.get(row, col) != 0 {
+            self.record_edit(|app| app.enter_value(row, col, 0));
+        } else {
+            self.record_edit(|app| app.grid.set_notes(row, col, CandidateSet::EMPTY));
+        }
+    }
+
+    fn handle_board_keys(&mut self, ctx: &egui::Context) {
+        const DIGITS: [egui::Key; 9] = [
+            egui::Key::Num1, egui::Key::Num2, egui::Key::Num3,
+            egui::Key::Num4, egui::Key::Num5, egui::Key::Num6,
+            egui::Key::Num7, egui::Key::Num8, egui::Key::Num9,
+        ];
+        const MOVES: [(egui::Key, egui::Key, isize, isize); 4] = [
+            (egui::Key::ArrowUp, egui::Key::W, -1, 0),
+            (egui::Key::ArrowDown, egui::Key::S, 1, 0),
+            (egui::Key::ArrowLeft, egui::Key::A, 0, -1),
+            (egui::Key::ArrowRight, egui::Key::D, 0, 1),
+        ];
+
+        // Leave keys alone while typing into a text field, such as the seed.
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let pressed = |key| ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, key));
+
+        for (arrow, letter, d_row, d_col) in MOVES {
+            if pressed(arrow) || pressed(letter) {
+                self.selected_cell = Some(match self.selected_cell {
+                    Some((row, col)) => (
+                        (row as isize + d_row).rem_euclid(9) as usize,
+                        (col as isize + d_col).rem_euclid(9) as usize,
+                    ),
+                    None => (0, 0),
+                });
+            }
+        }
+        if self.tab_pressed {
+            self.select_next_empty();
+        }
+        if !self.input_mode && pressed(egui::Key::N) {
+            self.notes_mode = !self.notes_mode;
+        }
+
+        let Some((row, col)) = self.selected_cell else {
+            return;
+        };
+        for (num, key) in (1..=9).zip(DIGITS) {
+            if pressed(key) {
+                self.press_digit(row, col, num);
+            }
+        }
+        if pressed(egui::Key::Backspace) || pressed(egui::Key::Delete) || pressed(egui::Key::Num0) {
+            self.clear_cell(row, col);
+        }
+    }
+
+    // Moves the selection to the next empty cell in reading order, wrapping
+    // around to the top.
+    fn select_next_empty(&mut self) {
+        let start = self.selected_cell.map_or(0, |(row, col)| row * 9 + col + 1);
+        self.selected_cell = (0..81)
+            .map(|offset| (start + offset) % 81)
+            .map(|i| (i / 9, i % 9))
+            .find(|&(row, col)| self.grid.get(row, col) == 0)
+            .or(self.selected_cell);
+    }
+
     fn draw_number_buttons(&mut self, ui: &mut egui::Ui){
         if self.selected_cell.is_some(){
             ui.label("Select a number");
             ui.horizontal(|ui| {
                 for num in 1..=9{
-                    if ui.button(num.to_string()).clicked(){
-                        if let Some((row, col)) = self.selected_cell{
-                            if self.input_mode {
-                                // In input mode, directly set the value
-                                self.grid.set(row, col, num);
-                            } else {
-                                // In play mode, use set_user
-                                self.grid.set_user(row, col, num);
-                            }
-                        }
+                    if ui.button(num.to_string()).clicked()
+                        && let Some((row, col)) = self.selected_cell{
+                        self.press_digit(row, col, num);
                     }
                 }
-                if ui.button("Clear").clicked(){
-                    if let Some((row, col)) = self.selected_cell{
-                        if self.input_mode {
-                            self.grid.set(row, col, 0);
-                        } else {
-                            self.grid.set_user(row, col, 0);
-                        }
-                    }
+                if ui.button("Clear").clicked()
+                    && let Some((row, col)) = self.selected_cell{
+                    self.clear_cell(row, col);
+                }
+                if !self.input_mode {
+                    ui.separator();
+                    ui.toggle_value(&mut self.notes_mode, "✏ Notes");
                 }
             });
         } else {
             ui.label("Select a cell to enter a number");
         }
+        ui.small("Arrows/WASD move · 1-9 place · 0/Backspace clear · N notes · Tab next empty");
     }
 }
\ No newline at end of file
@@ -1,2 +1,4 @@
 pub mod app;
-pub mod widgets;
\ No newline at end of file
+pub mod save;
+pub mod stats;
+pub mod widgets;
@@ -0,0 +1,229 @@
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::sudoku::{
+    Grid, History, Move, SolverKind,
+    generator::{Difficulty, GeneratorOptions},
+};
+use super::app::{MistakeMode, MistakeRules};
+
+/// Storage key of the game in progress.
+pub const GAME_KEY: &str = "game";
+/// Storage key of the player's preferences.
+pub const SETTINGS_KEY: &str = "settings";
+/// Storage key of the named save slots.
+pub const LIBRARY_KEY: &str = "library";
+/// Storage key of the slot the game in progress was saved to or loaded from.
+pub const CURRENT_SLOT_KEY: &str = "current_slot";
+
+/// Everything needed to pick a game up where it was left.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SavedGame {
+    pub grid: Grid,
+    pub solution: Option<Grid>,
+    /// What the game was generated from, `None` for custom puzzles.
+    pub game: Option<(Difficulty, GeneratorOptions, u64)>,
+    /// Saved while the player was still entering a custom puzzle.
+    pub input_mode: bool,
+    pub elapsed: Duration,
+    pub history: History,
+    pub mistakes: u32,
+    pub hints_used: u32,
+    pub game_won: bool,
+    pub solved_by_solver: bool,
+    /// Whether the finished game already went into the statistics. Saves
+    /// from before this existed leave it false; see [`SavedGame::counted`].
+    #[serde(default)]
+    pub recorded: bool,
+    /// Whether the player changed the board at all. Kept apart from
+    /// `history`, which solving and loading clear.
+    #[serde(default)]
+    pub moves_made: bool,
+    /// The rules the game is played under; see [`SavedGame::mistake_rules`].
+    #[serde(default)]
+    pub mistake_rules: Option<MistakeRules>,
+    /// Whether the game ran out of mistakes.
+    #[serde(default)]
+    pub lost: bool,
+}
+
+impl SavedGame {
+    /// Share of the board filled in, givens included, from 0 to 1.
+    pub fn progress(&self) -> f32 {
+        self.grid.filled_count() as f32 / 81.0
+    }
+
+    /// Whether the game is already in the statistics. Games are recorded the
+    /// moment they end, so one that had ended when it was saved was counted
+    /// then, even if the save predates `recorded`.
+    pub fn counted(&self, settings: &Settings) -> bool {
+        self.recorded || (!self.input_mode && (self.game_won || self.lost(settings)))
+    }
+
+    /// The rules the game is played under. Saves from before these were
+    /// kept were played under `settings`.
+    pub fn mistake_rules(&self, settings: &Settings) -> MistakeRules {
+        self.mistake_rules.unwrap_or(settings.mistake_rules())
+    }
+
+    /// Whether the game ran out of mistakes, judged by its own rules when
+    /// the save predates `lost`.
+    pub fn lost(&self, settings: &Settings) -> bool {
+        self.lost || self.mistake_rules(settings).exhausted_by(self.mistakes)
+    }
+
+    /// Whether the game was played. Saves from before `moves_made` only
+    /// have their history to go by.
+    pub fn played(&self) -> bool {
+        self.moves_made || self.history.can_undo()
+    }
+
+    /// Whether replacing this game with another counts as abandoning it:
+    /// it was played but hasn't ended.
+    pub fn abandoned_if_replaced(&self, settings: &Settings) -> bool {
+        self.played() && !self.counted(settings)
+    }
+
+    /// Whether the board, history or clock moved on from `earlier`.
+    pub fn changed_since(&self, earlier: &SavedGame) -> bool {
+        !Move::between(&earlier.grid, &self.grid).is_empty()
+            || self.history != earlier.history
+            || self.elapsed != earlier.elapsed
+    }
+}
+
+/// A game the player kept under a name.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SaveSlot {
+    pub name: String,
+    pub game: SavedGame,
+    pub last_played: SystemTime,
+}
+
+/// Named save slots, most recently played first. Names are unique.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Library {
+    slots: Vec<SaveSlot>,
+}
+
+impl Library {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn slots(&self) -> &[SaveSlot] {
+        &self.slots
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SaveSlot> {
+        self.slots.iter().find(|slot| slot.name == name)
+    }
+
+    /// Stores `game` under `name`, replacing any slot of that name.
+    pub fn save(&mut self, name: &str, game: SavedGame, now: SystemTime) {
+        self.slots.retain(|slot| slot.name != name);
+        self.slots.insert(
+            0,
+            SaveSlot {
+                name: name.to_owned(),
+                game,
+                last_played: now,
+            },
+        );
+    }
+
+    /// Stores `game` in the existing slot `name` if it was played since the
+    /// slot was last written, making that slot the most recent. Returns
+    /// whether anything changed.
+    pub fn update(&mut self, name: &str, game: SavedGame, now: SystemTime) -> bool {
+        match self.get(name) {
+            Some(slot) if game.changed_since(&slot.game) => {
+                self.save(name, game, now);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn delete(&mut self, name: &str) {
+        self.slots.retain(|slot| slot.name != name);
+    }
+
+    /// Copies a slot under a fresh name like "Name (2)", returning that name.
+    pub fn duplicate(&mut self, name: &str) -> Option<String> {
+        let slot = self.get(name)?.clone();
+        let copy_name = self.unused_name(name);
+        let position = self.slots.iter().position(|slot| slot.name == name)?;
+        self.slots.insert(
+            position + 1,
+            SaveSlot {
+                name: copy_name.clone(),
+                ..slot
+            },
+        );
+        Some(copy_name)
+    }
+
+    /// Renames a slot, returning false if `new_name` is blank or taken by
+    /// another slot.
+    pub fn rename(&mut self, name: &str, new_name: &str) -> bool {
+        let new_name = new_name.trim();
+        if new_name.is_empty() || (new_name != name && self.get(new_name).is_some()) {
+            return false;
+        }
+        match self.slots.iter_mut().find(|slot| slot.name == name) {
+            Some(slot) => {
+                slot.name = new_name.to_owned();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// `base`, or `base` with the lowest free number appended.
+    pub fn unused_name(&self, base: &str) -> String {
+        if self.get(base).is_none() {
+            return base.to_owned();
+        }
+        (2..)
+            .map(|n| format!("{base} ({n})"))
+            .find(|name| self.get(name).is_none())
+            .expect("some number is free")
+    }
+}
+
+/// Preferences kept across games. Missing fields fall back to their
+/// defaults, so older saves still load.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub auto_candidates: bool,
+    pub show_conflicts: bool,
+    pub mistake_mode: MistakeMode,
+    pub mistake_limit: u32,
+    pub solver_kind: SolverKind,
+    pub generator_options: GeneratorOptions,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            auto_candidates: false,
+            show_conflicts: true,
+            mistake_mode: MistakeMode::default(),
+            mistake_limit: 3,
+            solver_kind: SolverKind::default(),
+            generator_options: GeneratorOptions::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn mistake_rules(&self) -> MistakeRules {
+        MistakeRules {
+            mode: self.mistake_mode,
+            limit: self.mistake_limit,
+        }
+    }
+}
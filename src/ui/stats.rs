@@ -0,0 +1,133 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::sudoku::generator::Difficulty;
+
+/// File name of the finished-games log. It sits next to the app's other
+/// saved state, but in its own file so it outlives resets of that state.
+pub const STATS_FILE: &str = "stats.ron";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Outcome {
+    /// The player filled in the whole board.
+    Solved,
+    /// The player gave up and let a solver finish it.
+    SolvedBySolver,
+    /// The mistake limit ran out.
+    OutOfMistakes,
+    /// The player started another puzzle before finishing this one.
+    Abandoned,
+}
+
+/// One game that reached an end.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FinishedGame {
+    /// `None` for custom puzzles, which have no seed either.
+    pub difficulty: Option<Difficulty>,
+    pub seed: Option<u64>,
+    pub time: Duration,
+    pub mistakes: u32,
+    pub hints: u32,
+    pub outcome: Outcome,
+    pub finished_at: SystemTime,
+}
+
+impl FinishedGame {
+    pub fn won(&self) -> bool {
+        self.outcome == Outcome::Solved
+    }
+}
+
+/// Games played and won in some category, with times over the wins.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub played: usize,
+    pub won: usize,
+    pub best: Option<Duration>,
+    pub average: Option<Duration>,
+}
+
+/// Every finished game, oldest first.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct GameStats {
+    games: Vec<FinishedGame>,
+}
+
+impl GameStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the log written by [`GameStats::save`]. A missing file is an
+    /// empty log.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => ron::from_str(&text).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::new()),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Writes the log to `path`, creating its directory if needed.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        fs::write(path, text)
+    }
+
+    pub fn games(&self) -> &[FinishedGame] {
+        &self.games
+    }
+
+    pub fn record(&mut self, game: FinishedGame) {
+        self.games.push(game);
+    }
+
+    /// Totals for games of `difficulty`, with `None` meaning custom puzzles.
+    pub fn summary(&self, difficulty: Option<Difficulty>) -> Summary {
+        let games: Vec<&FinishedGame> = self.games.iter().filter(|game| game.difficulty == difficulty).collect();
+        let times: Vec<Duration> = games.iter().filter(|game| game.won()).map(|game| game.time).collect();
+        Summary {
+            played: games.len(),
+            won: times.len(),
+            best: times.iter().min().copied(),
+            average: (!times.is_empty()).then(|| times.iter().sum::<Duration>() / times.len() as u32),
+        }
+    }
+
+    /// Wins in a row up to the latest game.
+    pub fn current_streak(&self) -> usize {
+        self.games.iter().rev().take_while(|game| game.won()).count()
+    }
+
+    pub fn best_streak(&self) -> usize {
+        self.games
+            .split(|game| !game.won())
+            .map(<[FinishedGame]>::len)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Counts of winning times in buckets of `width`: bucket `i` covers
+    /// `i * width` up to `(i + 1) * width`. There are at most `max_buckets`,
+    /// the last one also taking every longer time. Empty when nothing was
+    /// won.
+    pub fn time_histogram(&self, width: Duration, max_buckets: usize) -> Vec<usize> {
+        let mut buckets = Vec::new();
+        for game in self.games.iter().filter(|game| game.won()) {
+            let bucket = ((game.time.as_secs_f64() / width.as_secs_f64()) as usize).min(max_buckets.max(1) - 1);
+            if bucket >= buckets.len() {
+                buckets.resize(bucket + 1, 0);
+            }
+            buckets[bucket] += 1;
+        }
+        buckets
+    }
+}
@@ -0,0 +1,97 @@
+use std::time::{Duration, Instant, SystemTime};
+
+/// A stopwatch that can be paused and resumed, adding up the time spent
+/// running. The `_at` variants take the current time explicitly.
+#[derive(Clone, Debug, Default)]
+pub struct Stopwatch {
+    accumulated: Duration,
+    running_since: Option<Instant>,
+}
+
+impl Stopwatch {
+    /// A paused stopwatch reading zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A paused stopwatch that already reads `elapsed`, e.g. when resuming
+    /// a saved game.
+    pub fn from_elapsed(elapsed: Duration) -> Self {
+        Self {
+            accumulated: elapsed,
+            running_since: None,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running_since.is_some()
+    }
+
+    pub fn start(&mut self) {
+        self.start_at(Instant::now());
+    }
+
+    pub fn start_at(&mut self, now: Instant) {
+        if self.running_since.is_none() {
+            self.running_since = Some(now);
+        }
+    }
+
+    pub fn pause(&mut self) {
+        self.pause_at(Instant::now());
+    }
+
+    pub fn pause_at(&mut self, now: Instant) {
+        if let Some(since) = self.running_since.take() {
+            self.accumulated += now.saturating_duration_since(since);
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed_at(Instant::now())
+    }
+
+    pub fn elapsed_at(&self, now: Instant) -> Duration {
+        match self.running_since {
+            Some(since) => self.accumulated + now.saturating_duration_since(since),
+            None => self.accumulated,
+        }
+    }
+}
+
+/// "m:ss", or "h:mm:ss" from an hour on.
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+/// "YYYY-MM-DD HH:MM" in UTC.
+pub fn format_date(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    let (days, secs_of_day) = (secs / 86_400, secs % 86_400);
+
+    // Days since 1970-01-01 to a civil date, after Howard Hinnant's
+    // `civil_from_days`, using eras of 400 years that start on March 1st.
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}",
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60
+    )
+}
@@ -0,0 +1,6 @@
+use sudoku_app::sudoku::Grid;
+
+/// Reads a puzzle in the 81-character format, givens fixed.
+pub fn grid_from(puzzle: &str) -> Grid {
+    puzzle.parse().unwrap_or_else(|error| panic!("bad puzzle {puzzle:?}: {error}"))
+}
@@ -0,0 +1,189 @@
+use sudoku_app::sudoku::{
+    GameCode, Generator, Rating, RatingBand, Solver, Technique,
+    generator::{Difficulty, GeneratorOptions, ParseGameCodeError, Symmetry},
+};
+
+#[test]
+fn generated_puzzles_have_a_unique_solution() {
+    for difficulty in [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard] {
+        for _ in 0..3 {
+            let grid = Generator::generate(difficulty).grid;
+            assert!(grid.is_valid(), "{difficulty:?} puzzle has conflicting givens");
+            assert!(
+                Solver::has_unique_solution(&grid),
+                "{difficulty:?} puzzle has more than one solution"
+            );
+        }
+    }
+}
+
+#[test]
+fn generated_givens_are_fixed() {
+    let grid = Generator::generate(Difficulty::Medium).grid;
+    for row in 0..9 {
+        for col in 0..9 {
+            assert_eq!(grid.get(row, col) != 0, grid.is_fixed(row, col));
+        }
+    }
+}
+
+#[test]
+fn generated_puzzles_fall_in_their_rating_band() {
+    let options = GeneratorOptions {
+        max_attempts: 10_000,
+        time_budget: None,
+        ..GeneratorOptions::default()
+    };
+    for difficulty in Difficulty::ALL {
+        let grid = Generator::generate_in_band(difficulty.band(), &options).grid;
+        let rating = Rating::of(&grid);
+        assert!(difficulty.band().contains(&rating), "{difficulty:?} rated {rating}");
+        assert!(Solver::has_unique_solution(&grid));
+    }
+}
+
+#[test]
+fn generation_can_target_a_single_technique() {
+    let options = GeneratorOptions {
+        max_attempts: 10_000,
+        time_budget: None,
+        ..GeneratorOptions::default()
+    };
+    let grid = Generator::generate_in_band(RatingBand::exactly(Technique::NakedPair), &options).grid;
+    assert_eq!(Rating::of(&grid).hardest, Some(Technique::NakedPair));
+}
+
+#[test]
+fn same_seed_gives_the_same_puzzle() {
+    let cells = |grid: &sudoku_app::sudoku::Grid| -> Vec<u8> {
+        (0..81).map(|i| grid.get(i / 9, i % 9)).collect()
+    };
+
+    let options = GeneratorOptions::default();
+    for difficulty in [Difficulty::Easy, Difficulty::Medium] {
+        let first = Generator::generate_seeded(difficulty, 123_456, &options).grid;
+        let second = Generator::generate_seeded(difficulty, 123_456, &options).grid;
+        assert_eq!(cells(&first), cells(&second));
+
+        let other = Generator::generate_seeded(difficulty, 654_321, &options).grid;
+        assert_ne!(cells(&first), cells(&other));
+    }
+}
+
+#[test]
+fn seeded_puzzles_land_in_their_band() {
+    let options = GeneratorOptions::default();
+    for difficulty in Difficulty::ALL {
+        for seed in 1..=2 {
+            let grid = Generator::generate_seeded(difficulty, seed, &options).grid;
+            let rating = Rating::of(&grid);
+            assert!(difficulty.band().contains(&rating), "{difficulty:?} #{seed} rated {rating}");
+        }
+    }
+}
+
+#[test]
+fn game_codes_round_trip() {
+    let code = GameCode {
+        difficulty: Difficulty::Expert,
+        seed: 42,
+        symmetry: Symmetry::Rotational180,
+        minimal: true,
+    };
+    assert_eq!(code.to_string(), "expert-42-rot180-min");
+    assert_eq!("expert-42-rot180-min".parse(), Ok(code));
+    assert_eq!(" Medium-7 ".parse::<GameCode>().map(|code| code.to_string()).as_deref(), Ok("medium-7"));
+
+    assert_eq!("42".parse::<GameCode>(), Err(ParseGameCodeError::UnknownDifficulty("42".to_owned())));
+    assert_eq!("hard-#42".parse::<GameCode>(), Err(ParseGameCodeError::InvalidSeed("#42".to_owned())));
+    assert_eq!("hard-42-spiral".parse::<GameCode>(), Err(ParseGameCodeError::UnknownOption("spiral".to_owned())));
+}
+
+#[test]
+fn game_codes_ignore_local_generation_limits() {
+    let local = GeneratorOptions {
+        max_attempts: 1,
+        symmetry: Symmetry::Vertical,
+        ..GeneratorOptions::default()
+    };
+    let code = GameCode::new(Difficulty::Medium, 5, &local);
+    assert_eq!(code.options().max_attempts, GeneratorOptions::default().max_attempts);
+
+    let shared: GameCode = code.to_string().parse().unwrap();
+    assert_eq!(shared.generate().grid.to_line(), code.generate().grid.to_line());
+}
+
+#[test]
+fn givens_follow_the_requested_symmetry() {
+    for symmetry in Symmetry::ALL {
+        let options = GeneratorOptions {
+            symmetry,
+            ..GeneratorOptions::default()
+        };
+        let grid = Generator::generate_seeded(Difficulty::Medium, 42, &options).grid;
+        assert!(Solver::has_unique_solution(&grid), "{symmetry:?}");
+
+        for row in 0..9 {
+            for col in 0..9 {
+                let given = grid.get(row, col) != 0;
+                for (r, c) in symmetry.orbit(row, col) {
+                    assert_eq!(given, grid.get(r, c) != 0, "{symmetry:?} at ({row}, {col})");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn orbits_match_their_symmetry() {
+    assert_eq!(Symmetry::None.orbit(1, 2), vec![(1, 2)]);
+    assert_eq!(Symmetry::Rotational180.orbit(1, 2), vec![(1, 2), (7, 6)]);
+    assert_eq!(Symmetry::Rotational90.orbit(1, 2), vec![(1, 2), (2, 7), (7, 6), (6, 1)]);
+    assert_eq!(Symmetry::Horizontal.orbit(1, 2), vec![(1, 2), (7, 2)]);
+    assert_eq!(Symmetry::Vertical.orbit(1, 2), vec![(1, 2), (1, 6)]);
+    assert_eq!(Symmetry::Diagonal.orbit(1, 2), vec![(1, 2), (2, 1)]);
+    assert_eq!(Symmetry::Rotational90.orbit(4, 4), vec![(4, 4)]);
+}
+
+#[test]
+fn minimal_puzzles_have_no_removable_givens() {
+    for symmetry in [Symmetry::None, Symmetry::Rotational180] {
+        let options = GeneratorOptions {
+            max_attempts: 1,
+            symmetry,
+            minimal: true,
+            ..GeneratorOptions::default()
+        };
+        let grid = Generator::generate_seeded(Difficulty::Hard, 7, &options).grid;
+        assert!(Solver::has_unique_solution(&grid));
+        assert!(grid.filled_count() < 40, "{} clues", grid.filled_count());
+
+        for row in 0..9 {
+            for col in 0..9 {
+                if grid.get(row, col) == 0 {
+                    continue;
+                }
+                let mut fewer = grid.clone();
+                for (r, c) in symmetry.orbit(row, col) {
+                    fewer.set(r, c, 0);
+                }
+                assert!(!Solver::has_unique_solution(&fewer), "given at ({row}, {col}) is removable");
+            }
+        }
+    }
+}
+
+#[test]
+fn puzzles_come_with_their_solution() {
+    let puzzle = Generator::generate_seeded(Difficulty::Medium, 99, &GeneratorOptions::default());
+    assert!(puzzle.solution.is_complete());
+
+    let mut solved = puzzle.grid.clone();
+    assert!(Solver::solve(&mut solved));
+    for row in 0..9 {
+        for col in 0..9 {
+            assert_eq!(solved.get(row, col), puzzle.solution.get(row, col));
+        }
+    }
+    assert!(puzzle.grid.mistakes(&puzzle.solution).iter().flatten().all(|&flag| !flag));
+}
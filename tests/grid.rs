@@ -0,0 +1,163 @@
+use sudoku_app::sudoku::{CandidateSet, Grid};
+
+#[test]
+fn placing_a_digit_removes_it_from_peer_notes() {
+    let mut grid = Grid::new();
+    for (row, col) in [(0, 8), (8, 0), (1, 1), (5, 5)] {
+        grid.toggle_note(row, col, 4);
+        grid.toggle_note(row, col, 7);
+    }
+    grid.toggle_note(0, 0, 4);
+
+    grid.set_user(0, 0, 4);
+
+    assert!(grid.notes(0, 0).is_empty());
+    for (row, col) in [(0, 8), (8, 0), (1, 1)] {
+        assert!(!grid.notes(row, col).contains(4));
+        assert!(grid.notes(row, col).contains(7));
+    }
+    // Not a peer of (0, 0).
+    assert!(grid.notes(5, 5).contains(4));
+}
+
+#[test]
+fn notes_only_go_in_empty_player_cells() {
+    let mut grid = Grid::new();
+    grid.set(0, 0, 5);
+    grid.set_fixed(0, 0, true);
+    grid.set_user(0, 1, 3);
+
+    grid.toggle_note(0, 0, 1);
+    grid.toggle_note(0, 1, 1);
+    grid.toggle_note(0, 2, 1);
+
+    assert!(grid.notes(0, 0).is_empty());
+    assert!(grid.notes(0, 1).is_empty());
+    assert_eq!(grid.notes(0, 2), CandidateSet::single(1));
+
+    grid.toggle_note(0, 2, 1);
+    assert!(grid.notes(0, 2).is_empty());
+}
+
+#[test]
+fn clearing_entries_clears_notes() {
+    let mut grid = Grid::new();
+    grid.toggle_note(4, 4, 2);
+    grid.set_user(3, 3, 9);
+    grid.clear_non_fixed();
+
+    assert_eq!(grid.get(3, 3), 0);
+    assert!(grid.notes(4, 4).is_empty());
+}
+
+#[test]
+fn fill_candidate_notes_matches_valid_numbers() {
+    let mut grid = Grid::new();
+    grid.set(0, 0, 1);
+    grid.set_fixed(0, 0, true);
+    grid.set(4, 4, 2);
+    grid.set_fixed(4, 4, true);
+    grid.fill_candidate_notes();
+
+    assert!(grid.notes(0, 0).is_empty());
+    for (row, col) in [(0, 1), (4, 0), (8, 8)] {
+        let expected: Vec<u8> = sudoku_app::sudoku::Solver::get_valid_numbers(&grid, row, col);
+        assert_eq!(grid.notes(row, col).iter().collect::<Vec<u8>>(), expected);
+    }
+}
+
+#[test]
+fn restoring_candidates_after_a_clear() {
+    let mut grid = Grid::new();
+    grid.set(0, 8, 5);
+    grid.set_fixed(0, 8, true);
+    grid.fill_candidate_notes();
+
+    grid.set_user(0, 0, 3);
+    grid.toggle_note(8, 0, 7);
+    grid.set_user(0, 0, 0);
+    grid.restore_candidate_notes(0, 0, 3);
+
+    // The cleared cell gets its candidates back; 5 is still blocked.
+    assert!(grid.notes(0, 0).contains(3));
+    assert!(!grid.notes(0, 0).contains(5));
+    // Peers get 3 back but keep eliminations the player made.
+    assert!(grid.notes(0, 4).contains(3));
+    assert!(grid.notes(8, 0).contains(3));
+    assert!(!grid.notes(8, 0).contains(7));
+}
+
+#[test]
+fn conflicts_flag_every_duplicate() {
+    let mut grid = Grid::new();
+    grid.set(0, 0, 5);
+    grid.set(0, 7, 5); // same row
+    grid.set(4, 4, 3);
+    grid.set(5, 3, 3); // same box
+    grid.set(8, 8, 5); // no clash with (0, 0) or (0, 7)
+
+    let conflicts = grid.conflicts();
+    let flagged: Vec<(usize, usize)> = (0..81)
+        .map(|i| (i / 9, i % 9))
+        .filter(|&(row, col)| conflicts[row][col])
+        .collect();
+    assert_eq!(flagged, vec![(0, 0), (0, 7), (4, 4), (5, 3)]);
+
+    grid.set(0, 7, 0);
+    grid.set(5, 3, 0);
+    assert!(grid.conflicts().iter().flatten().all(|&flag| !flag));
+}
+
+#[test]
+fn mistakes_compare_against_the_solution() {
+    let mut solution = Grid::new();
+    for row in 0..9 {
+        for col in 0..9 {
+            solution.set(row, col, ((row * 3 + row / 3 + col) % 9 + 1) as u8);
+        }
+    }
+    assert!(solution.is_complete());
+
+    let mut grid = Grid::new();
+    grid.set(0, 0, solution.get(0, 0));
+    // Wrong, though nothing else on the board clashes with it.
+    grid.set(4, 4, solution.get(4, 4) % 9 + 1);
+
+    let mistakes = grid.mistakes(&solution);
+    assert!(!mistakes[0][0]);
+    assert!(mistakes[4][4]);
+    assert!(grid.conflicts().iter().flatten().all(|&flag| !flag));
+    assert_eq!(mistakes.iter().flatten().filter(|&&flag| flag).count(), 1);
+}
+
+#[test]
+fn candidates_stay_in_sync_through_edits() {
+    // Recomputes a cell's candidates by scanning its units.
+    fn scanned(grid: &Grid, row: usize, col: usize) -> CandidateSet {
+        let mut candidates = CandidateSet::ALL;
+        for i in 0..9 {
+            let (box_row, box_col) = (row / 3 * 3 + i / 3, col / 3 * 3 + i % 3);
+            for value in [grid.get(row, i), grid.get(i, col), grid.get(box_row, box_col)] {
+                if value != 0 {
+                    candidates.remove(value);
+                }
+            }
+        }
+        candidates
+    }
+
+    let mut grid = Grid::new();
+    // Includes duplicates and clears, so digits leave and re-enter units.
+    let mut state: usize = 7;
+    for _ in 0..400 {
+        state = (state * 1_103_515_245 + 12_345) % (1 << 31);
+        let (cell, value) = (state % 81, (state / 81 % 10) as u8);
+        grid.set(cell / 9, cell % 9, value);
+
+        for row in 0..9 {
+            for col in 0..9 {
+                assert_eq!(grid.candidates(row, col), scanned(&grid, row, col), "({row}, {col})");
+            }
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use sudoku_app::sudoku::{CandidateSet, Grid, History, Move};
+
+mod common;
+use common::grid_from;
+
+const PUZZLE: &str = "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+
+fn play(grid: &mut Grid, history: &mut History, edit: impl FnOnce(&mut Grid)) {
+    let before = grid.clone();
+    edit(grid);
+    history.record(Move::between(&before, grid));
+}
+
+#[test]
+fn undo_and_redo_restore_placements_and_peer_notes() {
+    let mut grid = grid_from(PUZZLE);
+    grid.fill_candidate_notes();
+    let start = grid.clone();
+    let mut history = History::new();
+
+    play(&mut grid, &mut history, |g| g.set_user(0, 2, 4));
+    let placed = grid.clone();
+    assert!(start.notes(1, 2).contains(4));
+    assert!(!grid.notes(1, 2).contains(4));
+
+    assert!(history.undo(&mut grid));
+    assert!(Move::between(&start, &grid).is_empty());
+    assert!(grid.notes(1, 2).contains(4));
+
+    assert!(history.redo(&mut grid));
+    assert!(Move::between(&placed, &grid).is_empty());
+}
+
+#[test]
+fn note_edits_are_undoable() {
+    let mut grid = grid_from(PUZZLE);
+    let mut history = History::new();
+
+    play(&mut grid, &mut history, |g| g.toggle_note(0, 2, 1));
+    play(&mut grid, &mut history, |g| g.toggle_note(0, 2, 2));
+    assert_eq!(grid.notes(0, 2).len(), 2);
+
+    history.undo(&mut grid);
+    assert_eq!(grid.notes(0, 2), CandidateSet::single(1));
+    history.undo(&mut grid);
+    assert!(grid.notes(0, 2).is_empty());
+    assert!(!history.undo(&mut grid));
+}
+
+#[test]
+fn new_move_discards_redo() {
+    let mut grid = grid_from(PUZZLE);
+    let mut history = History::new();
+
+    play(&mut grid, &mut history, |g| g.set_user(0, 2, 4));
+    history.undo(&mut grid);
+    assert!(history.can_redo());
+
+    play(&mut grid, &mut history, |g| g.set_user(0, 3, 6));
+    assert!(!history.can_redo());
+    assert!(!history.redo(&mut grid));
+}
+
+#[test]
+fn moves_that_change_nothing_are_ignored() {
+    let mut grid = grid_from(PUZZLE);
+    let mut history = History::new();
+
+    // Givens can't be overwritten, so this edit is a no-op.
+    play(&mut grid, &mut history, |g| g.set_user(0, 0, 1));
+    assert!(!history.can_undo());
+}
+
+#[test]
+fn repeated_placements_only_place_once() {
+    let mut grid = grid_from(PUZZLE);
+    let mut history = History::new();
+
+    // (0, 2) is a 4 in the solution; pressing 1 twice is one wrong entry.
+    let before = grid.clone();
+    grid.set_user(0, 2, 1);
+    let first = Move::between(&before, &grid);
+    assert_eq!(first.placed(0, 2), Some(1));
+    history.record(first);
+
+    let before = grid.clone();
+    grid.set_user(0, 2, 1);
+    let second = Move::between(&before, &grid);
+    assert_eq!(second.placed(0, 2), None);
+
+    play(&mut grid, &mut history, |g| g.set_user(0, 2, 0));
+    let before = grid.clone();
+    grid.set_user(0, 2, 4);
+    assert_eq!(Move::between(&before, &grid).placed(0, 2), Some(4));
+}
@@ -0,0 +1,56 @@
+use eframe::egui;
+use sudoku_app::ui::app::take_tab_presses;
+
+fn tab(pressed: bool) -> egui::Event {
+    egui::Event::Key {
+        key: egui::Key::Tab,
+        physical_key: None,
+        pressed,
+        repeat: false,
+        modifiers: egui::Modifiers::NONE,
+    }
+}
+
+// One frame of a window with a couple of buttons, like the toolbar.
+fn run_frame(ctx: &egui::Context, events: Vec<egui::Event>) {
+    let input = egui::RawInput {
+        events,
+        ..Default::default()
+    };
+    let _ = ctx.run(input, |ctx| {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            let _ = ui.button("New Game");
+            let _ = ui.button("Solve");
+        });
+    });
+}
+
+#[test]
+fn tab_never_focuses_a_widget() {
+    // Unfiltered, egui hands focus to the first button.
+    let ctx = egui::Context::default();
+    run_frame(&ctx, vec![]);
+    run_frame(&ctx, vec![tab(true), tab(false)]);
+    assert!(ctx.memory(|memory| memory.focused()).is_some());
+
+    let ctx = egui::Context::default();
+    run_frame(&ctx, vec![]);
+    let mut input = egui::RawInput {
+        events: vec![tab(true), tab(false)],
+        ..Default::default()
+    };
+    assert!(take_tab_presses(&mut input));
+    assert!(input.events.is_empty());
+    run_frame(&ctx, input.events);
+    assert!(ctx.memory(|memory| memory.focused()).is_none());
+}
+
+#[test]
+fn releasing_tab_alone_is_not_a_press() {
+    let mut input = egui::RawInput {
+        events: vec![tab(false)],
+        ..Default::default()
+    };
+    assert!(!take_tab_presses(&mut input));
+    assert!(input.events.is_empty());
+}
@@ -0,0 +1,146 @@
+use sudoku_app::sudoku::{
+    Generator, Grid, LogicalSolver, SolverKind, Technique,
+    generator::Difficulty,
+    logic::LogicOutcome,
+};
+
+mod common;
+use common::grid_from;
+
+// Every placement must match the real solution and no elimination may remove
+// the solution's digit, whatever technique produced it.
+fn assert_deductions_are_sound(grid: &Grid) {
+    let mut solution = grid.clone();
+    assert!(SolverKind::DancingLinks.backend().solve(&mut solution));
+
+    let result = LogicalSolver::solve(grid);
+    for deduction in &result.deductions {
+        if let Some((row, col, digit)) = deduction.placement {
+            assert_eq!(solution.get(row, col), digit, "{deduction:?}");
+        }
+        for &(row, col, digit) in &deduction.eliminations {
+            assert_ne!(solution.get(row, col), digit, "{deduction:?}");
+        }
+    }
+}
+
+#[test]
+fn easy_puzzle_needs_only_singles() {
+    let grid = grid_from(
+        "530070000600195000098000060800060003400803001700020006060000280000419005000080079",
+    );
+    let result = LogicalSolver::solve(&grid);
+
+    assert_eq!(result.outcome, LogicOutcome::Solved);
+    assert!(result.grid.is_complete());
+    assert!(result.deductions.iter().all(|d| d.technique <= Technique::NakedSingle));
+}
+
+#[test]
+fn reports_when_stuck() {
+    // Needs techniques well beyond the ones implemented.
+    let grid = grid_from(
+        "800000000003600000070090200050007000000045700000100030001000068008500010090000400",
+    );
+    let result = LogicalSolver::solve(&grid);
+
+    assert_eq!(result.outcome, LogicOutcome::Stuck);
+    assert!(!result.grid.is_complete());
+    assert!(LogicalSolver::new(&result.grid).next_deduction().is_none());
+}
+
+#[test]
+fn harder_techniques_are_found_and_sound() {
+    let puzzles = [
+        // X-Wing
+        "100000569492056108056109240009640801064010000218035604040500016905061402621000005",
+        // Swordfish
+        "529410703006003002003200000052300076637050200190627530300069420200830600960742305",
+        // XY-Wing
+        "500100709029700000800000004004006000000030001005001007400805000098000020000004918",
+        // Hidden pair and naked triple
+        "000001030231090000065003100678924300103050006000136700009360570006019843300000000",
+    ];
+    let mut seen = Vec::new();
+    for puzzle in puzzles {
+        let grid = grid_from(puzzle);
+        assert_deductions_are_sound(&grid);
+        seen.extend(LogicalSolver::solve(&grid).deductions.iter().map(|d| d.technique));
+    }
+
+    for technique in [
+        Technique::XWing,
+        Technique::Swordfish,
+        Technique::XYWing,
+        Technique::HiddenPair,
+        Technique::NakedTriple,
+    ] {
+        assert!(seen.contains(&technique), "{technique:?} never used");
+    }
+}
+
+#[test]
+fn deductions_on_generated_puzzles_are_sound() {
+    for difficulty in [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard] {
+        assert_deductions_are_sound(&Generator::generate(difficulty).grid);
+    }
+}
+
+#[test]
+fn apply_keeps_candidates_consistent() {
+    let grid = grid_from(
+        "530070000600195000098000060800060003400803001700020006060000280000419005000080079",
+    );
+    let mut solver = LogicalSolver::new(&grid);
+    let deduction = solver.next_deduction().unwrap();
+    let (row, col, digit) = deduction.placement.unwrap();
+    solver.apply(&deduction);
+
+    assert_eq!(solver.grid().get(row, col), digit);
+    assert!(solver.candidates(row, col).is_empty());
+    for i in 0..9 {
+        assert!(!solver.candidates(row, i).contains(digit));
+        assert!(!solver.candidates(i, col).contains(digit));
+    }
+}
+
+#[test]
+fn hints_end_at_the_next_placement() {
+    // The XY-Wing puzzle needs eliminations before some placements.
+    for puzzle in [
+        "530070000600195000098000060800060003400803001700020006060000280000419005000080079",
+        "500100709029700000800000004004006000000030001005001007400805000098000020000004918",
+    ] {
+        let mut grid = grid_from(puzzle);
+        while !grid.is_complete() {
+            let steps = LogicalSolver::new(&grid).steps_to_next_placement();
+            let Some((last, before)) = steps.split_last() else {
+                panic!("no hint for {puzzle}");
+            };
+            assert!(before.iter().all(|step| step.placement.is_none()));
+            assert!(steps.iter().all(|step| !step.explain().is_empty()));
+
+            let Some((row, col, digit)) = last.placement else {
+                break;
+            };
+            grid.set(row, col, digit);
+        }
+        assert!(grid.is_complete(), "{puzzle}");
+    }
+}
+
+#[test]
+fn explanations_name_cells_and_units() {
+    let grid = grid_from(
+        "530070000600195000098000060800060003400803001700020006060000280000419005000080079",
+    );
+    let steps = LogicalSolver::new(&grid).steps_to_next_placement();
+    let [hint] = &steps[..] else {
+        panic!("expected a single step, got {steps:?}");
+    };
+    let (row, col, digit) = hint.placement.unwrap();
+    let text = hint.explain();
+    assert!(text.starts_with("Hidden Single: "), "{text}");
+    assert!(text.contains(&format!("{digit} can only go in r{}c{}", row + 1, col + 1)), "{text}");
+    assert!(text.contains("box "), "{text}");
+}
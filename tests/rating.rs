@@ -0,0 +1,40 @@
+use sudoku_app::sudoku::{Rating, Technique};
+
+mod common;
+use common::grid_from;
+
+#[test]
+fn rating_follows_the_hardest_technique() {
+    let easy = Rating::of(&grid_from(
+        "530070000600195000098000060800060003400803001700020006060000280000419005000080079",
+    ));
+    let x_wing = Rating::of(&grid_from(
+        "100000569492056108056109240009640801064010000218035604040500016905061402621000005",
+    ));
+
+    assert!(!easy.needs_guessing);
+    assert!(easy.score <= Technique::NakedSingle.score());
+    assert_eq!(x_wing.hardest, Some(Technique::XWing));
+    assert_eq!(x_wing.score, Technique::XWing.score());
+    assert!(easy.score < x_wing.score);
+}
+
+#[test]
+fn puzzles_beyond_logic_need_guessing() {
+    let rating = Rating::of(&grid_from(
+        "800000000003600000070090200050007000000045700000100030001000068008500010090000400",
+    ));
+    assert!(rating.needs_guessing);
+    assert_eq!(rating.score, Rating::GUESSING_SCORE);
+}
+
+#[test]
+fn solved_grid_rates_zero() {
+    let mut grid = grid_from(
+        "530070000600195000098000060800060003400803001700020006060000280000419005000080079",
+    );
+    assert!(sudoku_app::sudoku::Solver::solve(&mut grid));
+    let rating = Rating::of(&grid);
+    assert_eq!(rating.score, 0.0);
+    assert_eq!(rating.hardest, None);
+}
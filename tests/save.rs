@@ -0,0 +1,221 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use sudoku_app::sudoku::{Grid, History, Move, SolverKind};
+use sudoku_app::ui::app::{MistakeMode, MistakeRules};
+use sudoku_app::ui::save::{Library, SavedGame, Settings};
+
+mod common;
+use common::grid_from;
+
+const PUZZLE: &str = "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+
+#[test]
+fn grids_round_trip_with_notes_and_masks() {
+    let mut grid = grid_from(PUZZLE);
+    grid.set_user(0, 2, 4);
+    grid.toggle_note(0, 3, 6);
+    grid.toggle_note(0, 3, 2);
+
+    let text = ron::to_string(&grid).unwrap();
+    let loaded: Grid = ron::from_str(&text).unwrap();
+
+    assert!(Move::between(&grid, &loaded).is_empty());
+    for row in 0..9 {
+        for col in 0..9 {
+            assert_eq!(loaded.is_fixed(row, col), grid.is_fixed(row, col));
+            // Candidates come from the rebuilt masks.
+            assert_eq!(loaded.candidates(row, col), grid.candidates(row, col));
+        }
+    }
+}
+
+#[test]
+fn history_survives_a_save() {
+    let mut grid = grid_from(PUZZLE);
+    let mut history = History::new();
+    let before = grid.clone();
+    grid.set_user(0, 2, 4);
+    history.record(Move::between(&before, &grid));
+
+    let mut loaded: History = ron::from_str(&ron::to_string(&history).unwrap()).unwrap();
+    assert!(loaded.undo(&mut grid));
+    assert_eq!(grid.get(0, 2), 0);
+}
+
+#[test]
+fn missing_settings_fall_back_to_defaults() {
+    let settings: Settings = ron::from_str("(auto_candidates: true)").unwrap();
+    assert!(settings.auto_candidates);
+    assert!(settings.show_conflicts);
+    assert_eq!(settings.solver_kind, SolverKind::default());
+}
+
+fn saved(grid: Grid) -> SavedGame {
+    SavedGame {
+        grid,
+        solution: None,
+        game: None,
+        input_mode: false,
+        elapsed: Duration::from_secs(90),
+        history: History::new(),
+        mistakes: 0,
+        hints_used: 0,
+        game_won: false,
+        solved_by_solver: false,
+        recorded: false,
+        moves_made: false,
+        mistake_rules: None,
+        lost: false,
+    }
+}
+
+#[test]
+fn library_keeps_unique_names_most_recent_first() {
+    let at = |secs| UNIX_EPOCH + Duration::from_secs(secs);
+    let mut library = Library::new();
+    library.save("Morning", saved(grid_from(PUZZLE)), at(1));
+    library.save("Evening", saved(Grid::new()), at(2));
+    library.save("Morning", saved(Grid::new()), at(3));
+
+    let names: Vec<&str> = library.slots().iter().map(|slot| slot.name.as_str()).collect();
+    assert_eq!(names, ["Morning", "Evening"]);
+    assert_eq!(library.get("Morning").unwrap().game.progress(), 0.0);
+
+    assert_eq!(library.duplicate("Evening").as_deref(), Some("Evening (2)"));
+    assert_eq!(library.duplicate("Evening").as_deref(), Some("Evening (3)"));
+    assert!(!library.rename("Evening (3)", "Morning"));
+    assert!(!library.rename("Evening (3)", "  "));
+    assert!(library.rename("Evening (3)", "Night"));
+
+    library.delete("Evening (2)");
+    let names: Vec<&str> = library.slots().iter().map(|slot| slot.name.as_str()).collect();
+    assert_eq!(names, ["Morning", "Evening", "Night"]);
+}
+
+#[test]
+fn progress_counts_filled_cells() {
+    let game = saved(grid_from(PUZZLE));
+    assert_eq!(game.progress(), 30.0 / 81.0);
+}
+
+#[test]
+fn updates_only_touch_slots_that_were_played() {
+    let at = |secs| UNIX_EPOCH + Duration::from_secs(secs);
+    let mut library = Library::new();
+    library.save("Morning", saved(grid_from(PUZZLE)), at(1));
+    library.save("Evening", saved(grid_from(PUZZLE)), at(2));
+
+    assert!(!library.update("Morning", saved(grid_from(PUZZLE)), at(3)));
+    assert_eq!(library.slots()[0].name, "Evening");
+    assert_eq!(library.get("Morning").unwrap().last_played, at(1));
+
+    let mut played = saved(grid_from(PUZZLE));
+    played.elapsed += Duration::from_secs(10);
+    assert!(library.update("Morning", played, at(4)));
+    assert_eq!(library.slots()[0].name, "Morning");
+    assert_eq!(library.get("Morning").unwrap().last_played, at(4));
+
+    assert!(!library.update("Night", saved(Grid::new()), at(5)));
+    assert!(library.get("Night").is_none());
+}
+
+#[test]
+fn games_that_ended_before_saving_are_not_counted_again() {
+    let mut won = saved(grid_from(PUZZLE));
+    won.game_won = true;
+    won.recorded = true;
+    // Saves from before `recorded` existed don't have the field.
+    let text = ron::to_string(&won).unwrap().replace(",recorded:true", "");
+    assert!(!text.contains("recorded"));
+    let loaded: SavedGame = ron::from_str(&text).unwrap();
+    assert!(!loaded.recorded);
+    assert!(loaded.counted(&Settings::default()));
+
+    let mut lost = saved(grid_from(PUZZLE));
+    lost.mistakes = 3;
+    assert!(!lost.counted(&Settings::default()));
+    let limited = Settings {
+        mistake_mode: MistakeMode::Limited,
+        ..Settings::default()
+    };
+    assert!(lost.counted(&limited));
+
+    assert!(!saved(grid_from(PUZZLE)).counted(&limited));
+}
+
+#[test]
+fn restored_games_that_were_played_are_abandoned_when_replaced() {
+    let mut game = saved(grid_from(PUZZLE));
+    assert!(!game.abandoned_if_replaced(&Settings::default()));
+
+    // Loading, like solving, starts with an empty history.
+    game.grid.set_user(0, 2, 1);
+    game.moves_made = true;
+    let restored: SavedGame = ron::from_str(&ron::to_string(&game).unwrap()).unwrap();
+    assert!(!restored.history.can_undo());
+    assert!(restored.abandoned_if_replaced(&Settings::default()));
+
+    // A game that already ended was counted when it did.
+    let mut won = restored.clone();
+    won.game_won = true;
+    assert!(!won.abandoned_if_replaced(&Settings::default()));
+}
+
+#[test]
+fn older_saves_count_as_played_by_their_history() {
+    let mut game = saved(grid_from(PUZZLE));
+    let before = game.grid.clone();
+    game.grid.set_user(0, 2, 4);
+    game.history.record(Move::between(&before, &game.grid));
+    let text = ron::to_string(&game).unwrap().replace(",moves_made:false", "");
+    assert!(!text.contains("moves_made"));
+
+    let loaded: SavedGame = ron::from_str(&text).unwrap();
+    assert!(loaded.played());
+}
+
+#[test]
+fn games_keep_the_mistake_rules_they_started_with() {
+    let limited = Settings {
+        mistake_mode: MistakeMode::Limited,
+        ..Settings::default()
+    };
+    let off = Settings::default();
+
+    // Lost under a limit of 3: switching the preference off doesn't revive it.
+    let mut lost = saved(grid_from(PUZZLE));
+    lost.mistakes = 3;
+    lost.lost = true;
+    lost.mistake_rules = Some(limited.mistake_rules());
+    assert!(lost.lost(&off));
+    assert!(lost.counted(&off));
+
+    // Played with mistakes off: turning on a limit doesn't end it.
+    let mut relaxed = saved(grid_from(PUZZLE));
+    relaxed.mistakes = 5;
+    relaxed.mistake_rules = Some(MistakeRules {
+        mode: MistakeMode::Off,
+        limit: 3,
+    });
+    assert!(!relaxed.lost(&limited));
+    assert!(!relaxed.counted(&limited));
+
+    let restored: SavedGame = ron::from_str(&ron::to_string(&lost).unwrap()).unwrap();
+    assert_eq!(restored.mistake_rules(&off), limited.mistake_rules());
+}
+
+#[test]
+fn saving_under_a_taken_name_keeps_both_games() {
+    let at = |secs| UNIX_EPOCH + Duration::from_secs(secs);
+    let mut library = Library::new();
+    assert_eq!(library.unused_name("Morning"), "Morning");
+    library.save("Morning", saved(grid_from(PUZZLE)), at(1));
+
+    let name = library.unused_name("Morning");
+    assert_eq!(name, "Morning (2)");
+    library.save(&name, saved(Grid::new()), at(2));
+
+    assert_eq!(library.slots().len(), 2);
+    assert_eq!(library.get("Morning").unwrap().game.progress(), 30.0 / 81.0);
+    assert_eq!(library.unused_name("Morning"), "Morning (3)");
+}
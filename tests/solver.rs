@@ -0,0 +1,81 @@
+use sudoku_app::sudoku::{Grid, Solver, solver};
+
+mod common;
+use common::grid_from;
+
+const UNIQUE: &str =
+    "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+
+#[test]
+fn count_solutions_of_unique_puzzle() {
+    let grid = grid_from(UNIQUE);
+    assert_eq!(Solver::count_solutions(&grid, 10), 1);
+    assert!(Solver::has_unique_solution(&grid));
+    assert!(solver::is_unique(&grid));
+}
+
+#[test]
+fn count_solutions_respects_limit_and_leaves_grid_untouched() {
+    let grid = Grid::new();
+    assert_eq!(Solver::count_solutions(&grid, 5), 5);
+    assert_eq!(Solver::count_solutions(&grid, 0), 0);
+    assert!(!solver::is_unique(&grid));
+    assert!((0..9).all(|row| (0..9).all(|col| grid.get(row, col) == 0)));
+}
+
+#[test]
+fn conflicting_givens_have_no_solutions() {
+    let mut grid = grid_from(UNIQUE);
+    grid.set(0, 2, 5);
+    assert_eq!(Solver::count_solutions(&grid, 2), 0);
+    assert!(!solver::is_unique(&grid));
+}
+
+#[test]
+fn solutions_lists_every_alternative() {
+    let mut solved = grid_from(UNIQUE);
+    assert!(Solver::solve(&mut solved));
+
+    // Blanking a "deadly pattern" rectangle leaves exactly two solutions.
+    let mut grid = solved.clone();
+    let (r1, r2, c1, c2) = find_rectangle(&solved);
+    for (row, col) in [(r1, c1), (r1, c2), (r2, c1), (r2, c2)] {
+        grid.set(row, col, 0);
+    }
+
+    let all: Vec<Grid> = Solver::solutions(&grid).collect();
+    assert_eq!(all.len(), 2);
+    for solution in &all {
+        assert!(solution.is_complete());
+    }
+    assert_ne!(all[0].get(r1, c1), all[1].get(r1, c1));
+}
+
+#[test]
+fn solutions_of_a_full_grid_is_the_grid_itself() {
+    let mut solved = grid_from(UNIQUE);
+    assert!(Solver::solve(&mut solved));
+    let all: Vec<Grid> = Solver::solutions(&solved).collect();
+    assert_eq!(all.len(), 1);
+}
+
+// Finds rows r1, r2 in one band and columns c1, c2 in one stack such that the
+// four corners hold only two digits, swapped diagonally.
+fn find_rectangle(grid: &Grid) -> (usize, usize, usize, usize) {
+    for r1 in 0..9 {
+        for r2 in r1 + 1..9 {
+            for c1 in 0..9 {
+                for c2 in c1 + 1..9 {
+                    let same_boxes = r1 / 3 == r2 / 3 || c1 / 3 == c2 / 3;
+                    if same_boxes
+                        && grid.get(r1, c1) == grid.get(r2, c2)
+                        && grid.get(r1, c2) == grid.get(r2, c1)
+                    {
+                        return (r1, r2, c1, c2);
+                    }
+                }
+            }
+        }
+    }
+    panic!("no deadly pattern in solution");
+}
@@ -0,0 +1,129 @@
+use sudoku_app::sudoku::{Grid, SolveStats, SolverKind};
+
+mod common;
+use common::grid_from;
+
+fn cells(grid: &Grid) -> Vec<u8> {
+    (0..81).map(|i| grid.get(i / 9, i % 9)).collect()
+}
+
+// Puzzles every backend must agree on.
+const CORPUS: &[&str] = &[
+    // Unique solutions.
+    "530070000600195000098000060800060003400803001700020006060000280000419005000080079",
+    "003020600900305001001806400008102900700000008006708200002609500800203009005010300",
+    "800000000003600000070090200050007000000045700000100030001000068008500010090000400",
+    "100007090030020008009600500005300900010080002600004000300000010040000007007000300",
+    // Several solutions.
+    "000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+    "530070000600195000098000060800060003400803001700020006060000280000419005000080000",
+];
+
+// Puzzles no backend may solve: no solution, then conflicting givens.
+const UNSOLVABLE: &[&str] = &[
+    "516849732307605000809700065135060907472591006968370050253186074684207500791050608",
+    "123456780000000009000000000000000000000000000000000000000000000000000000000000000",
+    "550070000600195000098000060800060003400803001700020006060000280000419005000080079",
+];
+
+// 17-clue puzzles built to defeat row-major backtracking, which needs over a
+// minute on each in debug builds, so only the other backends run on them.
+const HARDEST: &[&str] = &[
+    "000000010400000000020000000000050407008000300001090000300400200050100000000806000",
+    "000000000000003085001020000000507000004000100090000000500000073002010000000040009",
+];
+
+#[test]
+fn backends_count_the_same_solutions() {
+    for puzzle in CORPUS.iter().chain(UNSOLVABLE) {
+        let grid = grid_from(puzzle);
+        let counts: Vec<usize> = SolverKind::ALL
+            .iter()
+            .map(|kind| kind.backend().count_solutions(&grid, 3))
+            .collect();
+        assert!(counts.windows(2).all(|w| w[0] == w[1]), "{puzzle}: {counts:?}");
+    }
+}
+
+#[test]
+fn backends_find_the_same_unique_solution() {
+    for puzzle in CORPUS {
+        let grid = grid_from(puzzle);
+        if SolverKind::DancingLinks.backend().count_solutions(&grid, 2) != 1 {
+            continue;
+        }
+
+        let solutions: Vec<Vec<u8>> = SolverKind::ALL
+            .iter()
+            .map(|kind| {
+                let mut solved = grid.clone();
+                assert!(kind.backend().solve(&mut solved), "{} failed on {puzzle}", kind.name());
+                assert!(solved.is_complete());
+                cells(&solved)
+            })
+            .collect();
+        assert!(solutions.windows(2).all(|w| w[0] == w[1]), "{puzzle}");
+    }
+}
+
+#[test]
+fn backends_reject_unsolvable_puzzles() {
+    for puzzle in UNSOLVABLE {
+        for kind in SolverKind::ALL {
+            let mut grid = grid_from(puzzle);
+            assert!(!kind.backend().solve(&mut grid), "{} solved {puzzle}", kind.name());
+        }
+    }
+}
+
+#[test]
+fn solve_keeps_the_givens() {
+    let grid = grid_from(CORPUS[0]);
+    for kind in SolverKind::ALL {
+        let mut solved = grid.clone();
+        assert!(kind.backend().solve(&mut solved));
+        for row in 0..9 {
+            for col in 0..9 {
+                if grid.get(row, col) != 0 {
+                    assert_eq!(grid.get(row, col), solved.get(row, col));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn smarter_backends_solve_the_hardest_puzzles() {
+    for kind in [SolverKind::DancingLinks, SolverKind::Propagating] {
+        let backend = kind.backend();
+        for puzzle in HARDEST {
+            let grid = grid_from(puzzle);
+            assert_eq!(backend.count_solutions(&grid, 2), 1, "{} on {puzzle}", kind.name());
+
+            let mut solved = grid.clone();
+            assert!(backend.solve(&mut solved), "{} on {puzzle}", kind.name());
+            assert!(solved.is_complete());
+            for i in 0..81 {
+                let given = grid.get(i / 9, i % 9);
+                assert!(given == 0 || given == solved.get(i / 9, i % 9));
+            }
+        }
+    }
+}
+
+#[test]
+fn solve_stats_reflect_the_search() {
+    let grid = grid_from(CORPUS[0]);
+    let empty_cells = (0..81).filter(|&i| grid.get(i / 9, i % 9) == 0).count();
+
+    let mut stats = SolveStats::default();
+    assert!(SolverKind::Backtracking.backend().solve_with_stats(&mut grid.clone(), &mut stats));
+    assert!(stats.nodes >= empty_cells as u64);
+    assert_eq!(stats.max_depth, empty_cells);
+    assert_eq!(stats.nodes - stats.backtracks, empty_cells as u64);
+
+    // An easy puzzle falls to singles alone, without any guessing.
+    let mut stats = SolveStats::default();
+    assert!(SolverKind::Propagating.backend().solve_with_stats(&mut grid.clone(), &mut stats));
+    assert_eq!(stats, SolveStats::default());
+}
@@ -0,0 +1,106 @@
+use std::fs;
+use std::time::{Duration, UNIX_EPOCH};
+
+use sudoku_app::sudoku::generator::Difficulty;
+use sudoku_app::ui::stats::{FinishedGame, GameStats, Outcome};
+
+fn game(difficulty: Option<Difficulty>, secs: u64, outcome: Outcome) -> FinishedGame {
+    FinishedGame {
+        difficulty,
+        seed: difficulty.map(|_| 1),
+        time: Duration::from_secs(secs),
+        mistakes: 0,
+        hints: 0,
+        outcome,
+        finished_at: UNIX_EPOCH,
+    }
+}
+
+#[test]
+fn summaries_time_only_wins() {
+    let mut stats = GameStats::new();
+    stats.record(game(Some(Difficulty::Easy), 300, Outcome::Solved));
+    stats.record(game(Some(Difficulty::Easy), 100, Outcome::SolvedBySolver));
+    stats.record(game(Some(Difficulty::Easy), 200, Outcome::Solved));
+    stats.record(game(Some(Difficulty::Hard), 900, Outcome::OutOfMistakes));
+
+    let easy = stats.summary(Some(Difficulty::Easy));
+    assert_eq!((easy.played, easy.won), (3, 2));
+    assert_eq!(easy.best, Some(Duration::from_secs(200)));
+    assert_eq!(easy.average, Some(Duration::from_secs(250)));
+
+    let hard = stats.summary(Some(Difficulty::Hard));
+    assert_eq!((hard.played, hard.won, hard.best), (1, 0, None));
+    assert_eq!(stats.summary(None).played, 0);
+}
+
+#[test]
+fn streaks_count_consecutive_wins() {
+    let mut stats = GameStats::new();
+    assert_eq!((stats.current_streak(), stats.best_streak()), (0, 0));
+
+    for outcome in [
+        Outcome::Solved,
+        Outcome::Solved,
+        Outcome::Solved,
+        Outcome::SolvedBySolver,
+        Outcome::Solved,
+    ] {
+        stats.record(game(Some(Difficulty::Medium), 60, outcome));
+    }
+    assert_eq!(stats.current_streak(), 1);
+    assert_eq!(stats.best_streak(), 3);
+
+    stats.record(game(None, 60, Outcome::OutOfMistakes));
+    assert_eq!(stats.current_streak(), 0);
+}
+
+#[test]
+fn abandoning_a_game_breaks_the_streak() {
+    let mut stats = GameStats::new();
+    stats.record(game(Some(Difficulty::Easy), 60, Outcome::Solved));
+    stats.record(game(Some(Difficulty::Easy), 60, Outcome::Solved));
+    stats.record(game(Some(Difficulty::Easy), 60, Outcome::Abandoned));
+    stats.record(game(Some(Difficulty::Easy), 60, Outcome::Solved));
+
+    assert_eq!((stats.current_streak(), stats.best_streak()), (1, 2));
+    let easy = stats.summary(Some(Difficulty::Easy));
+    assert_eq!((easy.played, easy.won), (4, 3));
+}
+
+#[test]
+fn stats_round_trip_through_their_file() {
+    let dir = std::env::temp_dir().join(format!("sudoku-stats-{}", std::process::id()));
+    let path = dir.join("stats.ron");
+    assert!(GameStats::load(&path).unwrap().games().is_empty());
+
+    let mut stats = GameStats::new();
+    stats.record(game(Some(Difficulty::Hard), 420, Outcome::Solved));
+    stats.record(game(None, 60, Outcome::Abandoned));
+    stats.save(&path).unwrap();
+
+    let loaded = GameStats::load(&path).unwrap();
+    assert_eq!(loaded.games().len(), 2);
+    assert_eq!(loaded.games()[1].outcome, Outcome::Abandoned);
+    assert_eq!(loaded.summary(Some(Difficulty::Hard)).best, Some(Duration::from_secs(420)));
+
+    fs::write(&path, "not stats").unwrap();
+    assert!(GameStats::load(&path).is_err());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn histogram_buckets_winning_times() {
+    let mut stats = GameStats::new();
+    for secs in [30, 59, 61, 250] {
+        stats.record(game(Some(Difficulty::Easy), secs, Outcome::Solved));
+    }
+    stats.record(game(Some(Difficulty::Easy), 700, Outcome::SolvedBySolver));
+
+    assert_eq!(stats.time_histogram(Duration::from_secs(60), 10), vec![2, 1, 0, 0, 1]);
+    assert!(GameStats::new().time_histogram(Duration::from_secs(60), 10).is_empty());
+
+    // Long games share the last bucket instead of widening the chart.
+    stats.record(game(Some(Difficulty::Easy), 36_000, Outcome::Solved));
+    assert_eq!(stats.time_histogram(Duration::from_secs(60), 3), vec![2, 1, 2]);
+}
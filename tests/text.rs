@@ -0,0 +1,70 @@
+use sudoku_app::sudoku::{Grid, ParseGridError};
+
+const PUZZLE: &str = "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+
+fn cells(grid: &Grid) -> String {
+    (0..81).map(|i| char::from(b'0' + grid.get(i / 9, i % 9))).collect()
+}
+
+#[test]
+fn parses_the_one_line_format() {
+    let grid: Grid = PUZZLE.parse().unwrap();
+    assert_eq!(cells(&grid), PUZZLE);
+    assert!(grid.is_fixed(0, 0));
+    assert!(!grid.is_fixed(0, 2));
+
+    let dotted: Grid = PUZZLE.replace('0', ".").parse().unwrap();
+    assert_eq!(cells(&dotted), PUZZLE);
+}
+
+#[test]
+fn skips_whitespace_and_grid_art() {
+    let art = "
+        5 3 . | . 7 . | . . .
+        6 . . | 1 9 5 | . . .
+        . 9 8 | . . . | . 6 .
+        ------+-------+------
+        8 . . | . 6 . | . . 3
+        4 . . | 8 . 3 | . . 1
+        7 . . | . 2 . | . . 6
+        ------+-------+------
+        . 6 . | . . . | 2 8 .
+        . . . | 4 1 9 | . . 5
+        . . . | . 8 . | . 7 9
+    ";
+    let grid: Grid = art.parse().unwrap();
+    assert_eq!(cells(&grid), PUZZLE);
+}
+
+#[test]
+fn reports_wrong_length_and_bad_characters() {
+    assert_eq!(PUZZLE[..80].parse::<Grid>().unwrap_err(), ParseGridError::WrongLength(80));
+    assert_eq!(format!("{PUZZLE}1").parse::<Grid>().unwrap_err(), ParseGridError::WrongLength(82));
+
+    let error = format!("{}\n12x", &PUZZLE[..9]).parse::<Grid>().unwrap_err();
+    assert_eq!(
+        error,
+        ParseGridError::InvalidCharacter {
+            character: 'x',
+            line: 2,
+            column: 3
+        }
+    );
+    assert_eq!(error.to_string(), "unexpected 'x' at line 2, column 3");
+}
+
+#[test]
+fn exports_round_trip() {
+    let grid: Grid = PUZZLE.parse().unwrap();
+    assert_eq!(grid.to_line(), PUZZLE.replace('0', "."));
+
+    let pretty = grid.to_pretty();
+    assert_eq!(pretty.lines().count(), 11);
+    assert_eq!(pretty.lines().next(), Some("5 3 . | . 7 . | . . ."));
+    assert_eq!(pretty.lines().nth(3), Some("------+-------+------"));
+
+    for text in [grid.to_line(), pretty] {
+        let reread: Grid = text.parse().unwrap();
+        assert_eq!(cells(&reread), PUZZLE);
+    }
+}
@@ -0,0 +1,38 @@
+use std::time::{Duration, Instant};
+
+use sudoku_app::utils::timer::{Stopwatch, format_date, format_duration};
+
+#[test]
+fn stopwatch_only_counts_running_time() {
+    let start = Instant::now();
+    let at = |secs| start + Duration::from_secs(secs);
+    let mut watch = Stopwatch::new();
+    assert_eq!(watch.elapsed_at(at(5)), Duration::ZERO);
+
+    watch.start_at(at(10));
+    assert_eq!(watch.elapsed_at(at(15)), Duration::from_secs(5));
+    // Starting again while running doesn't restart the lap.
+    watch.start_at(at(12));
+    watch.pause_at(at(20));
+    assert_eq!(watch.elapsed_at(at(100)), Duration::from_secs(10));
+
+    watch.pause_at(at(110));
+    watch.start_at(at(200));
+    assert!(watch.is_running());
+    assert_eq!(watch.elapsed_at(at(203)), Duration::from_secs(13));
+}
+
+#[test]
+fn durations_format_as_clock_time() {
+    assert_eq!(format_duration(Duration::from_secs(0)), "0:00");
+    assert_eq!(format_duration(Duration::from_millis(65_900)), "1:05");
+    assert_eq!(format_duration(Duration::from_secs(3600 + 2 * 60 + 3)), "1:02:03");
+}
+
+#[test]
+fn dates_format_in_utc() {
+    let at = |secs| std::time::UNIX_EPOCH + Duration::from_secs(secs);
+    assert_eq!(format_date(at(0)), "1970-01-01 00:00");
+    assert_eq!(format_date(at(951_782_400 + 3_723)), "2000-02-29 01:02");
+    assert_eq!(format_date(at(1_792_281_600)), "2026-10-18 00:00");
+}